@@ -1,25 +1,147 @@
 use actix_web::web::{scope, ServiceConfig};
-use crate::controllers::student_controller::{create_student_controller, delete_student_controller, get_all_students_paginate_controller, update_student_controller};
-use crate::controllers::user_controller::{create_user_controller, delete_user_controller, get_all_paginate_controller, update_user_controller, user_login_controller};
+use crate::controllers::email_verification_controller::{resend_verification_controller, verify_email_controller};
+use crate::controllers::me_controller::{change_password_controller, get_me_controller, update_me_controller};
+use crate::controllers::mfa_controller::{confirm_mfa_controller, enroll_mfa_controller, mfa_login_controller};
+use crate::controllers::oidc_controller::{oidc_callback_controller, oidc_login_controller};
+use crate::controllers::password_controller::{forgot_password_controller, reset_password_controller};
+use crate::controllers::token_controller::{jwks_controller, logout_controller, refresh_token_controller};
+use crate::controllers::course_controller::{create_course_controller, delete_course_controller, get_all_courses_paginate_controller, get_course_controller, update_course_controller};
+use crate::controllers::enrollment_controller::{drop_me_controller, enroll_me_controller, enroll_student_controller, get_my_courses_controller, get_roster_controller, set_enrollment_status_controller};
+use crate::controllers::teacher_controller::{create_teacher_controller, delete_teacher_controller, get_all_teachers_paginate_controller, get_teacher_students_controller, link_legacy_class_teachers_controller};
+use crate::controllers::section_controller::{create_section_controller, delete_section_controller, get_all_sections_paginate_controller, get_section_students_controller, move_students_controller, remove_student_from_section_controller, update_section_controller};
+use crate::controllers::student_controller::{create_student_controller, delete_student_controller, get_all_students_paginate_controller, get_student_terms_controller, update_student_controller};
+use crate::controllers::academic_year_controller::{create_academic_year_controller, delete_academic_year_controller, get_academic_year_terms_controller, get_all_academic_years_controller, update_academic_year_controller};
+use crate::controllers::term_controller::{activate_term_controller, create_term_controller, delete_term_controller, get_active_term_controller, update_term_controller};
+use crate::controllers::session_controller::{get_my_sessions_controller, get_user_sessions_controller, revoke_my_session_controller, revoke_user_session_controller};
+use crate::controllers::service_account_controller::{create_api_key_controller, create_service_account_controller, delete_service_account_controller, get_all_service_accounts_controller, get_api_keys_controller, revoke_api_key_controller};
+use crate::controllers::role_controller::{create_role_controller, get_all_roles_controller, grant_permission_controller, revoke_permission_controller};
+use crate::controllers::user_controller::{create_user_controller, delete_user_controller, get_all_paginate_controller, get_all_lockout_events_controller, add_user_role_controller, change_role_controller, get_user_roles_controller, impersonate_user_controller, remove_user_role_controller, revoke_user_tokens_controller, unlock_user_controller, update_user_controller, user_login_controller};
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
 
 pub fn app_config(cfg: &mut ServiceConfig) {
 
     // configuring routing *********************
+    // scope guards are the minimum for every route in the scope, routes add their own on top
 
     cfg
         .service(user_login_controller) // login
+        .service(mfa_login_controller) // second login step with a TOTP or recovery code
         .service(create_user_controller) // sign up
+        .service(refresh_token_controller) // rotate refresh token
+        .service(logout_controller) // revoke current token
+        .service(jwks_controller) // public signing keys
+        .service(forgot_password_controller) // mail a password reset token
+        .service(reset_password_controller) // set a new password with the token
+        .service(verify_email_controller) // confirm the email address from the signup mail
+        .service(resend_verification_controller) // mail a new verification link
+        .service(oidc_login_controller) // single sign-on at the identity provider
+        .service(oidc_callback_controller) // return from the identity provider
+        .service(enroll_mfa_controller) // start two-factor enrollment
+        .service(confirm_mfa_controller) // enable two-factor authentication
         .service(
             scope("/users")
+                .wrap(Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Read)))
                 .service(update_user_controller)
                 .service(delete_user_controller)
                 .service(get_all_paginate_controller)
+                .service(revoke_user_tokens_controller)
+                .service(impersonate_user_controller)
+                .service(change_role_controller)
+                .service(get_user_roles_controller)
+                .service(add_user_role_controller)
+                .service(remove_user_role_controller)
+                .service(unlock_user_controller)
+                .service(get_all_lockout_events_controller)
+                .service(get_user_sessions_controller)
+                .service(revoke_user_session_controller)
+        )
+        .service(
+            scope("/me") // the caller's own account, any signed in user
+                .service(get_me_controller)
+                .service(update_me_controller)
+                .service(change_password_controller)
+                .service(get_my_sessions_controller)
+                .service(revoke_my_session_controller)
+                .service(get_my_courses_controller)
+        )
+        .service(
+            scope("/roles")
+                .wrap(Authorize::new(&[Role::Admin], Permission::new(Resource::Roles, Action::Read)))
+                .service(get_all_roles_controller)
+                .service(create_role_controller)
+                .service(grant_permission_controller)
+                .service(revoke_permission_controller)
+        )
+        .service(
+            scope("/service-accounts")
+                .wrap(Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Read)))
+                .service(get_all_service_accounts_controller)
+                .service(create_service_account_controller)
+                .service(delete_service_account_controller)
+                .service(get_api_keys_controller)
+                .service(create_api_key_controller)
+                .service(revoke_api_key_controller)
         )
         .service(
             scope("/students")
+                .wrap(Authorize::new(&[Role::Admin, Role::Student, Role::Teacher, Role::User], Permission::new(Resource::Students, Action::Read)).with_custom_roles())
                 .service(create_student_controller)
                 .service(update_student_controller)
                 .service(delete_student_controller)
                 .service(get_all_students_paginate_controller)
+                .service(get_student_terms_controller)
+        )
+        .service(
+            scope("/teachers")
+                .wrap(Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Teachers, Action::Read)).with_custom_roles())
+                .service(create_teacher_controller)
+                .service(delete_teacher_controller)
+                .service(get_all_teachers_paginate_controller)
+                .service(get_teacher_students_controller)
+                .service(link_legacy_class_teachers_controller)
+        )
+        .service(
+            scope("/sections")
+                .wrap(Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Sections, Action::Read)))
+                .service(create_section_controller)
+                .service(update_section_controller)
+                .service(delete_section_controller)
+                .service(get_all_sections_paginate_controller)
+                .service(get_section_students_controller)
+                .service(move_students_controller)
+                .service(remove_student_from_section_controller)
+        )
+        .service(
+            scope("/academic-years")
+                .wrap(Authorize::new(&[Role::Admin, Role::Student, Role::Teacher], Permission::new(Resource::Terms, Action::Read)))
+                .service(create_academic_year_controller)
+                .service(update_academic_year_controller)
+                .service(delete_academic_year_controller)
+                .service(get_all_academic_years_controller)
+                .service(get_academic_year_terms_controller)
+        )
+        .service(
+            scope("/terms")
+                .wrap(Authorize::new(&[Role::Admin, Role::Student, Role::Teacher], Permission::new(Resource::Terms, Action::Read)))
+                .service(create_term_controller)
+                .service(update_term_controller)
+                .service(delete_term_controller)
+                .service(get_active_term_controller)
+                .service(activate_term_controller)
+        )
+        .service(
+            scope("/courses")
+                .wrap(Authorize::new(&[Role::Admin, Role::Student, Role::Teacher, Role::User], Permission::new(Resource::Courses, Action::Read)).with_custom_roles())
+                .service(create_course_controller)
+                .service(update_course_controller)
+                .service(delete_course_controller)
+                .service(get_all_courses_paginate_controller)
+                .service(enroll_student_controller)
+                .service(set_enrollment_status_controller)
+                .service(enroll_me_controller)
+                .service(drop_me_controller)
+                .service(get_roster_controller)
+                .service(get_course_controller)
         );
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use std::env;
-use log::{error, info};
+use log::info;
 use sea_orm::{Database, DatabaseConnection};
 
 
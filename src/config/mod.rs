@@ -1,3 +1,4 @@
 pub mod database;
 pub mod app;
-pub mod governor;
\ No newline at end of file
+pub mod governor;
+pub mod schema;
\ No newline at end of file
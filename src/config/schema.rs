@@ -0,0 +1,360 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use log::{info, warn};
+use sea_orm::{ActiveEnum, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Schema, Set, Statement, TransactionTrait};
+use sea_orm::sea_query::{ColumnDef, Expr, OnConflict, Table, TableAlterStatement};
+use uuid::Uuid;
+use crate::midleware::permission::{all_permissions, default_role_permissions, Action, Role};
+use crate::models::course_model::CourseStatus;
+use crate::models::enrollment_model::EnrollmentStatus;
+use crate::models::user_model::UserRole;
+use crate::models::{academic_year_model, course_model, permission_model, role_model, role_permission_model, term_model, user_model, AcademicYear, ApiKey, ApiKeyPermission, Course, Enrollment, EmailVerificationToken, LockoutEvent, OidcLoginState, PasswordResetToken, PermissionEntity, RecoveryCode, RefreshToken, RevokedToken, RoleEntity, RolePermission, Section, ServiceAccount, Session, StudentTerm, Teacher, Term, User, UserRoleEntity};
+use crate::repo::teacher_repo::{link_legacy_class_teachers_repo, unlinked_legacy_class_teachers_count_repo};
+use crate::repo::term_repo::carry_over_placements_repo;
+
+
+/// Create the tables this service owns when they don't exist yet
+pub async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
+    create_table(db, RefreshToken).await?;
+    create_table(db, RevokedToken).await?;
+    create_table(db, RoleEntity).await?;
+    create_table(db, PermissionEntity).await?;
+    create_table(db, RolePermission).await?;
+    create_table(db, PasswordResetToken).await?;
+    create_table(db, EmailVerificationToken).await?;
+    create_table(db, LockoutEvent).await?;
+    create_table(db, RecoveryCode).await?;
+    create_table(db, ServiceAccount).await?;
+    create_table(db, ApiKey).await?;
+    create_table(db, ApiKeyPermission).await?;
+    create_table(db, OidcLoginState).await?;
+    create_table(db, Session).await?;
+    create_table(db, UserRoleEntity).await?;
+    create_enum::<CourseStatus>(db, "course_status").await?;
+    create_table(db, Course).await?;
+    create_table(db, AcademicYear).await?;
+    create_table(db, Term).await?;
+    db.execute_unprepared("CREATE UNIQUE INDEX IF NOT EXISTS idx_terms_active ON terms (is_active) WHERE is_active").await?;
+    create_enum::<EnrollmentStatus>(db, "enrollment_status").await?;
+    create_table(db, Enrollment).await?;
+    create_table(db, Teacher).await?;
+    create_table(db, Section).await?;
+    migrate_section_academic_years(db).await?;
+    db.execute_unprepared("CREATE UNIQUE INDEX IF NOT EXISTS idx_sections_grade_letter_year_id ON sections (grade_level, section_letter, academic_year_id)").await?;
+    create_table(db, StudentTerm).await?;
+
+    // columns added to tables that already exist
+    alter_table(db, Table::alter()
+        .table(User)
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::TokensRevokedAt).timestamp_with_time_zone().null())
+        // accounts created before verification existed count as verified
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::EmailVerified).boolean().not_null().default(true))
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::FailedLoginAttempts).integer().not_null().default(0))
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::LockedUntil).timestamp_with_time_zone().null())
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::TotpSecret).string().null())
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::TotpEnabled).boolean().not_null().default(false))
+        .add_column_if_not_exists(ColumnDef::new(user_model::Column::TotpLastStep).big_integer().null())
+        .to_owned(),
+    ).await?;
+    alter_table(db, Table::alter()
+        .table(Course)
+        .add_column_if_not_exists(ColumnDef::new(course_model::Column::Capacity).integer().null())
+        .to_owned(),
+    ).await?;
+
+    migrate_user_roles(db).await?;
+    seed_roles(db).await?;
+    db.execute_unprepared("ALTER TABLE students ADD COLUMN IF NOT EXISTS section_id uuid NULL REFERENCES sections (id) ON DELETE SET NULL").await?;
+    migrate_class_teachers(db).await?;
+    migrate_terms(db).await?;
+
+    info!("database schema is up to date");
+    Ok(())
+}
+
+// permissions are defined in code, roles are only seeded once so admin changes survive restarts
+async fn seed_roles(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let existing: Vec<String> = PermissionEntity::find().all(db).await?.into_iter().map(|permission| permission.name).collect();
+    let new_permissions: Vec<String> = all_permissions().into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !existing.contains(name))
+        .collect();
+    let permissions = all_permissions().into_iter().map(|(name, description)| permission_model::ActiveModel {
+        name: Set(name),
+        description: Set(description),
+    });
+    PermissionEntity::insert_many(permissions)
+        .on_conflict(OnConflict::column(permission_model::Column::Name).do_nothing().to_owned())
+        .do_nothing()
+        .exec(db)
+        .await?;
+    migrate_unscoped_permissions(db).await?;
+
+    // permissions of a resource added in a later version are granted once to the built-in roles that default to them
+    if !existing.is_empty() {
+        for (role, permissions) in default_role_permissions() {
+            if RoleEntity::find_by_id(role.as_str()).one(db).await?.is_none() {
+                continue;
+            }
+            for permission in permissions.iter().filter(|permission| new_permissions.contains(permission)) {
+                RolePermission::insert(role_permission_model::ActiveModel {
+                    role_name: Set(role.as_str().to_string()),
+                    permission_name: Set(permission.clone()),
+                }).on_conflict(OnConflict::columns([role_permission_model::Column::RoleName, role_permission_model::Column::PermissionName]).do_nothing().to_owned())
+                    .do_nothing()
+                    .exec(db)
+                    .await?;
+                info!("built-in role {} granted {}", role.as_str(), permission);
+            }
+        }
+    }
+
+    // built-in roles added in a later version are seeded on their own, existing roles keep their changes
+    for (role, permissions) in default_role_permissions() {
+        if RoleEntity::find_by_id(role.as_str()).one(db).await?.is_some() {
+            continue;
+        }
+        let description = match role {
+            Role::Admin => "Full access, manages users and roles",
+            Role::User => "Registered user",
+            Role::Student => "Student with read access",
+            Role::Teacher => "Teacher, sees students and courses",
+            Role::Custom(_) => "",
+        };
+        RoleEntity::insert(role_model::ActiveModel {
+            name: Set(role.as_str().to_string()),
+            description: Set(description.to_string()),
+            built_in: Set(true),
+            created_at: Set(Utc::now()),
+        }).exec(db).await?;
+
+        RolePermission::insert_many(permissions.iter().map(|permission| role_permission_model::ActiveModel {
+            role_name: Set(role.as_str().to_string()),
+            permission_name: Set(permission.clone()),
+        })).exec(db).await?;
+        info!("built-in role {} seeded", role.as_str());
+    }
+    Ok(())
+}
+
+// permissions used to be plain Read/Write/Delete on every resource, they become *:read, *:write, *:delete
+async fn migrate_unscoped_permissions(db: &DatabaseConnection) -> Result<(), DbErr> {
+    for action in Action::all() {
+        let legacy = match action {
+            Action::Read => "Read",
+            Action::Write => "Write",
+            Action::Delete => "Delete",
+        };
+        if PermissionEntity::find_by_id(legacy).one(db).await?.is_none() {
+            continue;
+        }
+
+        RolePermission::update_many()
+            .col_expr(role_permission_model::Column::PermissionName, Expr::value(format!("*:{}", action.as_str())))
+            .filter(role_permission_model::Column::PermissionName.eq(legacy))
+            .exec(db)
+            .await?;
+        PermissionEntity::delete_by_id(legacy).exec(db).await?;
+        info!("permission {} migrated to *:{}", legacy, action.as_str());
+    }
+    Ok(())
+}
+
+// students.class_teacher was a free-text name, it becomes a reference to a teacher,
+// matched once when the column is added, names matching no teacher stay in the old column for an admin to assign
+async fn migrate_class_teachers(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let migrated = db.query_one(Statement::from_string(backend,
+        "SELECT 1 FROM information_schema.columns WHERE table_name = 'students' AND column_name = 'class_teacher_id'",
+    )).await?.is_some();
+    if migrated {
+        return Ok(());
+    }
+
+    let txn = db.begin().await?;
+    txn.execute_unprepared("ALTER TABLE students ADD COLUMN class_teacher_id uuid NULL REFERENCES teachers (id) ON DELETE SET NULL").await?;
+    txn.execute_unprepared("ALTER TABLE students ALTER COLUMN class_teacher DROP NOT NULL").await?;
+
+    let linked = link_legacy_class_teachers_repo(&txn).await.map_err(|e| DbErr::Custom(e.to_string()))?;
+    if linked > 0 {
+        info!("{} students linked to their class teacher", linked);
+    }
+    let unlinked = unlinked_legacy_class_teachers_count_repo(&txn).await.map_err(|e| DbErr::Custom(e.to_string()))?;
+    if unlinked > 0 {
+        warn!("{} students have a class teacher name that matches no teacher, create the teachers and call POST /teachers/link-legacy-class-teachers", unlinked);
+    }
+    txn.commit().await
+}
+
+// sections named their academic year, they now reference it, years missing from academic_years are created
+async fn migrate_section_academic_years(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let legacy = db.query_one(Statement::from_string(backend,
+        "SELECT 1 FROM information_schema.columns WHERE table_name = 'sections' AND column_name = 'academic_year'",
+    )).await?.is_some();
+    if !legacy {
+        return Ok(());
+    }
+
+    let txn = db.begin().await?;
+    txn.execute_unprepared("ALTER TABLE sections ADD COLUMN IF NOT EXISTS academic_year_id uuid NULL REFERENCES academic_years (id)").await?;
+    // section years were validated as e.g. 2026-2027, school years run from August to July
+    let created = txn.execute_unprepared(
+        "INSERT INTO academic_years (id, name, start_date, end_date, created_at) \
+        SELECT gen_random_uuid(), years.name, make_date(split_part(years.name, '-', 1)::int, 8, 1), make_date(split_part(years.name, '-', 2)::int, 7, 31), now() \
+        FROM (SELECT DISTINCT academic_year AS name FROM sections) years \
+        WHERE NOT EXISTS (SELECT 1 FROM academic_years ay WHERE ay.name = years.name)",
+    ).await?.rows_affected();
+    if created > 0 {
+        info!("{} academic years created from section years", created);
+    }
+    txn.execute_unprepared("UPDATE sections SET academic_year_id = ay.id FROM academic_years ay WHERE ay.name = sections.academic_year").await?;
+    txn.execute_unprepared("ALTER TABLE sections ALTER COLUMN academic_year_id SET NOT NULL").await?;
+    txn.execute_unprepared("DROP INDEX IF EXISTS idx_sections_grade_letter_year").await?;
+    txn.execute_unprepared("ALTER TABLE sections DROP COLUMN academic_year").await?;
+    txn.commit().await?;
+
+    info!("sections linked to their academic year");
+    Ok(())
+}
+
+// enrollments and placements had no term, existing ones go into a term covering the current school year
+async fn migrate_terms(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let has_students = db.query_one(Statement::from_string(backend, "SELECT 1 FROM students LIMIT 1")).await?.is_some();
+    let has_enrollments = db.query_one(Statement::from_string(backend, "SELECT 1 FROM enrollments LIMIT 1")).await?.is_some();
+    if Term::find().one(db).await?.is_none() && (has_students || has_enrollments) {
+        // school years run from August to July
+        let today = Utc::now().date_naive();
+        let start_year = if today.month() >= 8 { today.year() } else { today.year() - 1 };
+        let name = format!("{}-{}", start_year, start_year + 1);
+        let academic_year = match AcademicYear::find().filter(academic_year_model::Column::Name.eq(&name)).one(db).await? {
+            Some(academic_year) => academic_year,
+            None => AcademicYear::insert(academic_year_model::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                name: Set(name.clone()),
+                start_date: Set(NaiveDate::from_ymd_opt(start_year, 8, 1).unwrap()),
+                end_date: Set(NaiveDate::from_ymd_opt(start_year + 1, 7, 31).unwrap()),
+                created_at: Set(Utc::now()),
+            }).exec_with_returning(db).await?,
+        };
+        let term = Term::insert(term_model::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            academic_year_id: Set(academic_year.id),
+            name: Set("Full year".to_string()),
+            start_date: Set(academic_year.start_date),
+            end_date: Set(academic_year.end_date),
+            is_active: Set(true),
+            created_at: Set(Utc::now()),
+        }).exec_with_returning(db).await?;
+        let carried_over = carry_over_placements_repo(db, term.id, academic_year.id).await.map_err(|e| DbErr::Custom(e.to_string()))?;
+        warn!("no terms yet, term {} of {} created as the active term with {} student placements, adjust its dates", term.name, academic_year.name, carried_over);
+    }
+
+    let scoped = db.query_one(Statement::from_string(backend,
+        "SELECT 1 FROM information_schema.columns WHERE table_name = 'enrollments' AND column_name = 'term_id'",
+    )).await?.is_some();
+    if !scoped {
+        let txn = db.begin().await?;
+        txn.execute_unprepared("ALTER TABLE enrollments ADD COLUMN term_id uuid NULL REFERENCES terms (id) ON DELETE CASCADE").await?;
+        txn.execute_unprepared("UPDATE enrollments SET term_id = (SELECT id FROM terms WHERE is_active)").await?;
+        txn.execute_unprepared("ALTER TABLE enrollments ALTER COLUMN term_id SET NOT NULL").await?;
+        txn.execute_unprepared(r#"ALTER TABLE enrollments DROP CONSTRAINT "pk-enrollments""#).await?;
+        txn.execute_unprepared(r#"ALTER TABLE enrollments ADD CONSTRAINT "pk-enrollments" PRIMARY KEY (course_id, student_id, term_id)"#).await?;
+        txn.commit().await?;
+        info!("enrollments scoped to the active term");
+    }
+    Ok(())
+}
+
+// the student role used to be called Guest and users.role was free text, it becomes the user_role enum
+async fn migrate_user_roles(db: &DatabaseConnection) -> Result<(), DbErr> {
+    if let Some(guest) = RoleEntity::find_by_id("Guest").one(db).await? {
+        if RoleEntity::find_by_id(Role::Student.as_str()).one(db).await?.is_none() {
+            RoleEntity::insert(role_model::ActiveModel {
+                name: Set(Role::Student.as_str().to_string()),
+                description: Set(guest.description),
+                built_in: Set(true),
+                created_at: Set(guest.created_at),
+            }).exec(db).await?;
+        }
+        let permissions = RolePermission::find()
+            .filter(role_permission_model::Column::RoleName.eq("Guest"))
+            .all(db)
+            .await?;
+        if !permissions.is_empty() {
+            RolePermission::insert_many(permissions.into_iter().map(|permission| role_permission_model::ActiveModel {
+                role_name: Set(Role::Student.as_str().to_string()),
+                permission_name: Set(permission.permission_name),
+            }))
+                .on_conflict(OnConflict::columns([role_permission_model::Column::RoleName, role_permission_model::Column::PermissionName]).do_nothing().to_owned())
+                .do_nothing()
+                .exec(db)
+                .await?;
+        }
+        RoleEntity::delete_by_id("Guest").exec(db).await?;
+        info!("role Guest renamed to Student");
+    }
+
+    create_enum::<UserRole>(db, "user_role").await?;
+    // values added after the type was created
+    db.execute_unprepared("ALTER TYPE user_role ADD VALUE IF NOT EXISTS 'Teacher'").await?;
+
+    let backend = db.get_database_backend();
+    let column_type = db.query_one(Statement::from_string(backend,
+        "SELECT data_type FROM information_schema.columns WHERE table_name = 'users' AND column_name = 'role'",
+    )).await?;
+    let column_type: Option<String> = match column_type {
+        Some(row) => Some(row.try_get("", "data_type")?),
+        None => None,
+    };
+    if column_type.is_none_or(|data_type| data_type == "USER-DEFINED") {
+        return Ok(());
+    }
+
+    db.execute_unprepared("UPDATE users SET role = 'Student' WHERE role = 'Guest'").await?;
+    // no guessing a role for unknown values, the wrong one could grant too much
+    let unknown = db.query_all(Statement::from_string(backend,
+        "SELECT role, COUNT(*) AS users FROM users WHERE role NOT IN ('Admin', 'User', 'Student', 'Teacher') GROUP BY role ORDER BY role",
+    )).await?;
+    if !unknown.is_empty() {
+        let mut roles = Vec::new();
+        for row in unknown {
+            let role: String = row.try_get("", "role")?;
+            let users: i64 = row.try_get("", "users")?;
+            roles.push(format!("{} ({} users)", role, users));
+        }
+        return Err(DbErr::Migration(format!(
+            "users.role holds unknown roles: {}, set these users to Admin, User, Student or Teacher and grant custom roles through user_roles, then restart",
+            roles.join(", ")
+        )));
+    }
+    db.execute_unprepared("ALTER TABLE users ALTER COLUMN role TYPE user_role USING role::user_role").await?;
+    info!("users.role migrated to the user_role enum");
+    Ok(())
+}
+
+async fn create_table<E: EntityTrait>(db: &DatabaseConnection, entity: E) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    let mut statement = schema.create_table_from_entity(entity);
+
+    db.execute(backend.build(statement.if_not_exists())).await?;
+    Ok(())
+}
+
+// postgres has no CREATE TYPE IF NOT EXISTS
+async fn create_enum<E: ActiveEnum>(db: &DatabaseConnection, name: &str) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let exists = db.query_one(Statement::from_sql_and_values(backend, "SELECT 1 FROM pg_type WHERE typname = $1", [name.into()])).await?;
+    if exists.is_none() {
+        let schema = Schema::new(backend);
+        db.execute(backend.build(&schema.create_enum_from_active_enum::<E>())).await?;
+    }
+    Ok(())
+}
+
+async fn alter_table(db: &DatabaseConnection, statement: TableAlterStatement) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&statement)).await?;
+    Ok(())
+}
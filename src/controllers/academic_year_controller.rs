@@ -0,0 +1,114 @@
+use actix_web::{delete, get, post, put, HttpResponse};
+use actix_web::web::{Data, Json, Path};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::academic_year_model::AcademicYearRequestDto;
+use crate::services::academic_year_service::{create_academic_year_service, delete_academic_year_service, get_all_academic_years_service, update_academic_year_service};
+use crate::services::term_service::get_academic_year_terms_service;
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Write))")]
+pub async fn create_academic_year_controller(db: Data<DatabaseConnection>, dto: Json<AcademicYearRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_academic_year_service(&db, dto.into_inner(), &user).await {
+        Ok(academic_year) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "academic year has created".to_string(),
+                data: academic_year,
+            };
+            info!("academic year has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("academic year not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[put("/update/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Write))")]
+pub async fn update_academic_year_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<AcademicYearRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match update_academic_year_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(academic_year) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "academic year has updated".to_string(),
+                data: academic_year,
+            };
+            info!("academic year successfully updated: {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("academic year not updated : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Delete))")]
+pub async fn delete_academic_year_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_academic_year_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
+
+#[get("/get-all-academic-years")]
+pub async fn get_all_academic_years_controller(db: Data<DatabaseConnection>) -> HttpResponse {
+    match get_all_academic_years_service(&db).await {
+        Ok(academic_years) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All academic years".to_string(),
+                data: academic_years,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all academic years {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/{id}/terms")]
+pub async fn get_academic_year_terms_controller(db: Data<DatabaseConnection>, id: Path<String>) -> HttpResponse {
+    match get_academic_year_terms_service(&db, id.to_string()).await {
+        Ok(terms) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "academic year's terms".to_string(),
+                data: terms,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get academic year's terms {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use actix_web::{delete, get, post, put, HttpResponse};
+use actix_web::web::{Data, Json, Path, Query};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::course_model::{CourseQueryOptions, CourseRequestDto};
+use crate::services::course_service::{create_course_service, delete_course_service, get_all_courses_paginate_service, get_course_service, update_course_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Courses, Action::Write))")]
+pub async fn create_course_controller(db: Data<DatabaseConnection>, dto: Json<CourseRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_course_service(&db, dto.into_inner(), &user).await {
+        Ok(course) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "course has created".to_string(),
+                data: course,
+            };
+            info!("course has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("course not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[put("/update/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Courses, Action::Write))")]
+pub async fn update_course_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<CourseRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match update_course_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(course) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "course has updated".to_string(),
+                data: course,
+            };
+            info!("course successfully updated: {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("course not updated : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Courses, Action::Delete))")]
+pub async fn delete_course_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_course_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
+
+#[get("/get-all-courses", wrap = "Authorize::new(&[Role::Admin, Role::Student, Role::Teacher, Role::User], Permission::new(Resource::Courses, Action::Read)).with_custom_roles()")]
+pub async fn get_all_courses_paginate_controller(db: Data<DatabaseConnection>, query: Query<CourseQueryOptions>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    let search_text = query.search_text.clone().unwrap_or("".to_string());
+    match get_all_courses_paginate_service(&db, search_text, query.status, query.page, query.size, &user).await {
+        Ok(courses) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All courses".to_string(),
+                data: courses,
+            };
+            info!("All courses: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all courses {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/{id}", wrap = "Authorize::new(&[Role::Admin, Role::Student, Role::Teacher, Role::User], Permission::new(Resource::Courses, Action::Read)).with_custom_roles()")]
+pub async fn get_course_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match get_course_service(&db, id.to_string(), &user).await {
+        Ok(course) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "course".to_string(),
+                data: course,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get course {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
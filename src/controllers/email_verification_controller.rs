@@ -0,0 +1,62 @@
+use actix_web::{get, post, HttpResponse};
+use actix_web::web::{Data, Json, Query};
+use log::{error, info, warn};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::models::email_verification_token_model::{ResendVerificationRequestDto, VerifyEmailQuery};
+use crate::services::email_verification_service::{resend_verification_mail_service, verify_email_service};
+use crate::utill::generic_response::GenericResponse;
+
+// opened from the link in the verification mail
+#[get("/verify-email")]
+pub async fn verify_email_controller(db: Data<DatabaseConnection>, query: Query<VerifyEmailQuery>) -> HttpResponse {
+    // input validation
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match verify_email_service(&db, query.into_inner().token).await {
+        Ok(user) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "email has verified".to_string(),
+                data: user.email,
+            };
+            info!("Email verified : {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => {
+            warn!("email verification rejected : {}", e);
+            HttpResponse::BadRequest().body(e)
+        }
+        Err(e) => {
+            error!("Failed to verify email {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// a new link for accounts whose mail got lost or expired
+#[post("/verify-email/resend")]
+pub async fn resend_verification_controller(db: Data<DatabaseConnection>, dto: Json<ResendVerificationRequestDto>) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match resend_verification_mail_service(&db, dto.into_inner()).await {
+        Ok(_) => {
+            let res = GenericResponse {
+                code: 202,
+                message: "if the email is registered and not verified yet a new verification link has been sent".to_string(),
+                data: (),
+            };
+            HttpResponse::Accepted().json(res)
+        }
+        Err(e) => {
+            error!("Failed to resend verification mail {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
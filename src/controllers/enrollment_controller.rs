@@ -0,0 +1,105 @@
+use actix_web::{get, post, put, HttpResponse};
+use actix_web::web::{Data, Json, Path, Query};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::enrollment_model::{EnrollRequestDto, EnrollmentResponseDto, EnrollmentStatusRequestDto};
+use crate::models::term_model::TermQueryOptions;
+use crate::services::enrollment_service::{drop_me_service, enroll_me_service, enroll_student_service, get_my_courses_service, get_roster_service, set_enrollment_status_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/{id}/enrollments", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Courses, Action::Write))")]
+pub async fn enroll_student_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<EnrollRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    enrollment_response(enroll_student_service(&db, id.to_string(), dto.into_inner(), &user).await)
+}
+
+#[put("/{id}/enrollments/{student_id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Courses, Action::Write))")]
+pub async fn set_enrollment_status_controller(db: Data<DatabaseConnection>, path: Path<(String, String)>, dto: Json<EnrollmentStatusRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    let (id, student_id) = path.into_inner();
+
+    enrollment_response(set_enrollment_status_service(&db, id, student_id, dto.into_inner(), &user).await)
+}
+
+#[post("/{id}/enroll", wrap = "Authorize::new(&[Role::Student], Permission::new(Resource::Enrollments, Action::Write))")]
+pub async fn enroll_me_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    enrollment_response(enroll_me_service(&db, id.to_string(), &user).await)
+}
+
+#[post("/{id}/drop", wrap = "Authorize::new(&[Role::Student], Permission::new(Resource::Enrollments, Action::Write))")]
+pub async fn drop_me_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    enrollment_response(drop_me_service(&db, id.to_string(), &user).await)
+}
+
+#[get("/{id}/roster", wrap = "Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Courses, Action::Read)).with_custom_roles()")]
+pub async fn get_roster_controller(db: Data<DatabaseConnection>, id: Path<String>, query: Query<TermQueryOptions>, user: AuthenticatedUser) -> HttpResponse {
+    match get_roster_service(&db, id.to_string(), query.term_id, &user).await {
+        Ok(roster) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "course roster".to_string(),
+                data: roster,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::ForbiddenError(e)) => HttpResponse::Forbidden().body(SystemError::ForbiddenError(e).to_string()),
+        Err(e) => {
+            error!("Failed get course roster {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// the caller's enrollments in a term, in the /me scope
+#[get("/courses")]
+pub async fn get_my_courses_controller(db: Data<DatabaseConnection>, query: Query<TermQueryOptions>, user: AuthenticatedUser) -> HttpResponse {
+    match get_my_courses_service(&db, query.term_id, &user).await {
+        Ok(courses) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "your courses".to_string(),
+                data: courses,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get my courses {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+
+fn enrollment_response(result: Result<EnrollmentResponseDto, SystemError>) -> HttpResponse {
+    match result {
+        Ok(enrollment) => {
+            let message = format!("enrollment is {:?}", enrollment.status).to_lowercase();
+            let res = GenericResponse {
+                code: 200,
+                message,
+                data: enrollment,
+            };
+            info!("Enrollment changed: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("Enrollment not changed : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
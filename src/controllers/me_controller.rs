@@ -0,0 +1,96 @@
+use actix_web::{get, patch, post, HttpResponse};
+use actix_web::web::{Data, Json};
+use log::{error, info, warn};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::{PasswordError, SystemError};
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::user_model::{ChangePasswordRequestDto, UpdateProfileRequestDto};
+use crate::services::user_service::{change_password_service, get_me_service, update_me_service};
+use crate::utill::generic_response::GenericResponse;
+
+// the caller's own profile, in the /me scope
+#[get("")]
+pub async fn get_me_controller(db: Data<DatabaseConnection>, user: AuthenticatedUser) -> HttpResponse {
+    match get_me_service(&db, &user).await {
+        Ok(me) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "your profile".to_string(),
+                data: me,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get profile {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// changing the email takes the current password and signs out the other sessions
+#[patch("")]
+pub async fn update_me_controller(db: Data<DatabaseConnection>, dto: Json<UpdateProfileRequestDto>, user: AuthenticatedUser, client: ClientInfo) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match update_me_service(&db, dto.into_inner(), &user, client).await {
+        Ok(me) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "profile has updated".to_string(),
+                data: me,
+            };
+            info!("Profile successfully updated: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(SystemError::ValidationError(e).to_string()),
+        Err(SystemError::PasswordError(PasswordError::InvalidPassword)) => {
+            warn!("email change rejected for {}", user.email);
+            HttpResponse::BadRequest().body(PasswordError::InvalidPassword.to_string())
+        }
+        Err(SystemError::AccountLocked(retry_after)) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .body(SystemError::AccountLocked(retry_after).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Profile not updated : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// other sessions are signed out, the calling one stays
+#[post("/password")]
+pub async fn change_password_controller(db: Data<DatabaseConnection>, dto: Json<ChangePasswordRequestDto>, user: AuthenticatedUser, client: ClientInfo) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match change_password_service(&db, dto.into_inner(), &user, client).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::PasswordError(PasswordError::InvalidPassword)) => {
+            warn!("password change rejected for {}", user.email);
+            HttpResponse::BadRequest().body(PasswordError::InvalidPassword.to_string())
+        }
+        Err(SystemError::FieldValidationError(errors)) => HttpResponse::BadRequest().json(GenericResponse {
+            code: 400,
+            message: "validation failed".to_string(),
+            data: errors,
+        }),
+        Err(SystemError::AccountLocked(retry_after)) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .body(SystemError::AccountLocked(retry_after).to_string()),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Password not changed : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
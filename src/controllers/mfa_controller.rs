@@ -0,0 +1,91 @@
+use actix_web::{post, HttpResponse};
+use actix_web::web::{Data, Json};
+use log::{error, info, warn};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::{PasswordError, SystemError};
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::recovery_code_model::{MfaConfirmRequestDto, MfaLoginRequestDto};
+use crate::services::mfa_service::{confirm_mfa_service, enroll_mfa_service, mfa_login_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/mfa/enroll")]
+pub async fn enroll_mfa_controller(db: Data<DatabaseConnection>, user: AuthenticatedUser) -> HttpResponse {
+    match enroll_mfa_service(&db, &user).await {
+        Ok(enrollment) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "scan the otpauth uri and confirm with a code".to_string(),
+                data: enrollment,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(e) => {
+            error!("Failed to start two-factor enrollment {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/mfa/confirm")]
+pub async fn confirm_mfa_controller(db: Data<DatabaseConnection>, dto: Json<MfaConfirmRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match confirm_mfa_service(&db, dto.into_inner(), &user).await {
+        Ok(recovery_codes) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "two-factor authentication has enabled, store the recovery codes".to_string(),
+                data: recovery_codes,
+            };
+            info!("two-factor authentication enabled for {}", user.email);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::PasswordError(PasswordError::InvalidMfaCode)) => {
+            HttpResponse::BadRequest().body(PasswordError::InvalidMfaCode.to_string())
+        }
+        Err(e) => {
+            error!("Failed to confirm two-factor enrollment {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/login/mfa")]
+pub async fn mfa_login_controller(db: Data<DatabaseConnection>, dto: Json<MfaLoginRequestDto>, client: ClientInfo) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match mfa_login_service(&db, dto.into_inner(), client).await {
+        Ok(tokens) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "successfully logged in".to_string(),
+                data: tokens,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::JwtError(e)) => {
+            warn!("mfa login rejected : {:?}", e);
+            HttpResponse::Unauthorized().body(e.to_string())
+        }
+        Err(SystemError::PasswordError(PasswordError::InvalidMfaCode)) => {
+            HttpResponse::Unauthorized().body(PasswordError::InvalidMfaCode.to_string())
+        }
+        Err(SystemError::AccountLocked(retry_after)) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .body(SystemError::AccountLocked(retry_after).to_string()),
+        Err(e) => {
+            error!("Failed mfa login {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
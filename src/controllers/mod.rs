@@ -1,2 +1,17 @@
 pub mod user_controller;
 pub mod student_controller;
+pub mod token_controller;
+pub mod role_controller;
+pub mod password_controller;
+pub mod email_verification_controller;
+pub mod mfa_controller;
+pub mod service_account_controller;
+pub mod oidc_controller;
+pub mod session_controller;
+pub mod me_controller;
+pub mod course_controller;
+pub mod enrollment_controller;
+pub mod teacher_controller;
+pub mod section_controller;
+pub mod academic_year_controller;
+pub mod term_controller;
@@ -0,0 +1,61 @@
+use actix_web::{get, HttpResponse};
+use actix_web::web::{Data, Query};
+use log::{error, info, warn};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::oidc_login_state_model::OidcCallbackQuery;
+use crate::services::oidc_service::{oidc_callback_service, oidc_login_service};
+use crate::utill::generic_response::GenericResponse;
+
+// sends the browser to the identity provider
+#[get("/oidc/login")]
+pub async fn oidc_login_controller(db: Data<DatabaseConnection>) -> HttpResponse {
+    match oidc_login_service(&db).await {
+        Ok(url) => HttpResponse::Found().insert_header(("Location", url)).finish(),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::OidcProviderError(e)) => {
+            error!("identity provider unavailable : {}", e);
+            HttpResponse::BadGateway().body(SystemError::OidcProviderError(e).to_string())
+        }
+        Err(e) => {
+            error!("Failed to start single sign-on {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// the identity provider redirects back here with the authorization code
+#[get("/oidc/callback")]
+pub async fn oidc_callback_controller(db: Data<DatabaseConnection>, query: Query<OidcCallbackQuery>, client: ClientInfo) -> HttpResponse {
+    // input validation
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match oidc_callback_service(&db, query.into_inner(), client).await {
+        Ok(token) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "successfully logged in".to_string(),
+                data: token,
+            };
+            info!("User successfully logged in with single sign-on");
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::OidcError(e)) => {
+            warn!("single sign-on refused : {}", e);
+            HttpResponse::Unauthorized().body(SystemError::OidcError(e).to_string())
+        }
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::OidcProviderError(e)) => {
+            error!("identity provider unavailable : {}", e);
+            HttpResponse::BadGateway().body(SystemError::OidcProviderError(e).to_string())
+        }
+        Err(e) => {
+            error!("Failed single sign-on {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
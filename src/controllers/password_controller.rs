@@ -0,0 +1,57 @@
+use actix_web::{post, HttpResponse};
+use actix_web::web::{Data, Json};
+use log::{error, warn};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::{PasswordError, SystemError};
+use crate::models::password_reset_token_model::{ForgotPasswordRequestDto, ResetPasswordRequestDto};
+use crate::services::password_service::{forgot_password_service, reset_password_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/password/forgot")]
+pub async fn forgot_password_controller(db: Data<DatabaseConnection>, dto: Json<ForgotPasswordRequestDto>) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match forgot_password_service(&db, dto.into_inner()).await {
+        Ok(_) => {
+            let res = GenericResponse {
+                code: 202,
+                message: "if the email is registered a password reset link has been sent".to_string(),
+                data: (),
+            };
+            HttpResponse::Accepted().json(res)
+        }
+        Err(e) => {
+            error!("Failed to start password reset {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/password/reset")]
+pub async fn reset_password_controller(db: Data<DatabaseConnection>, dto: Json<ResetPasswordRequestDto>) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match reset_password_service(&db, dto.into_inner()).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::PasswordError(PasswordError::InvalidResetToken)) => {
+            warn!("password reset rejected, invalid token");
+            HttpResponse::BadRequest().body(PasswordError::InvalidResetToken.to_string())
+        }
+        Err(SystemError::FieldValidationError(errors)) => HttpResponse::BadRequest().json(GenericResponse {
+            code: 400,
+            message: "validation failed".to_string(),
+            data: errors,
+        }),
+        Err(e) => {
+            error!("Failed to reset password {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
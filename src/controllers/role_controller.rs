@@ -0,0 +1,105 @@
+use actix_web::{delete, get, post, HttpResponse};
+use actix_web::web::{Data, Json, Path};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::role_model::RoleRequestDto;
+use crate::services::role_service::{create_role_service, get_all_roles_service, grant_permission_service, revoke_permission_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[get("/get-all-roles", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Roles, Action::Read))")]
+pub async fn get_all_roles_controller(db: Data<DatabaseConnection>) -> HttpResponse {
+    match get_all_roles_service(&db).await {
+        Ok(roles) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All roles".to_string(),
+                data: roles,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all roles {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Roles, Action::Write))")]
+pub async fn create_role_controller(db: Data<DatabaseConnection>, dto: Json<RoleRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_role_service(&db, dto.into_inner(), &user).await {
+        Ok(role) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "role has created".to_string(),
+                data: role,
+            };
+            info!("role has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("role not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/{name}/grant/{permission}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Roles, Action::Write))")]
+pub async fn grant_permission_controller(db: Data<DatabaseConnection>, path: Path<(String, String)>, user: AuthenticatedUser) -> HttpResponse {
+    let (name, permission) = path.into_inner();
+
+    match grant_permission_service(&db, name, permission, &user).await {
+        Ok(role) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "permission has granted".to_string(),
+                data: role,
+            };
+            info!("permission has granted {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("permission not granted : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("/{name}/revoke/{permission}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Roles, Action::Write))")]
+pub async fn revoke_permission_controller(db: Data<DatabaseConnection>, path: Path<(String, String)>, user: AuthenticatedUser) -> HttpResponse {
+    let (name, permission) = path.into_inner();
+
+    match revoke_permission_service(&db, name, permission, &user).await {
+        Ok(role) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "permission has revoked".to_string(),
+                data: role,
+            };
+            info!("permission has revoked {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("permission not revoked : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
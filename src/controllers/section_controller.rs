@@ -0,0 +1,157 @@
+use actix_web::{delete, get, post, put, HttpResponse};
+use actix_web::web::{Data, Json, Path, Query};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::section_model::{MoveStudentsRequestDto, SectionQueryOptions, SectionRequestDto};
+use crate::models::term_model::TermQueryOptions;
+use crate::services::section_service::{create_section_service, delete_section_service, get_all_sections_paginate_service, get_section_students_service, move_students_service, remove_student_from_section_service, update_section_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Sections, Action::Write))")]
+pub async fn create_section_controller(db: Data<DatabaseConnection>, dto: Json<SectionRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_section_service(&db, dto.into_inner(), &user).await {
+        Ok(section) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "section has created".to_string(),
+                data: section,
+            };
+            info!("section has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("section not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[put("/update/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Sections, Action::Write))")]
+pub async fn update_section_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<SectionRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match update_section_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(section) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "section has updated".to_string(),
+                data: section,
+            };
+            info!("section successfully updated: {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("section not updated : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Sections, Action::Delete))")]
+pub async fn delete_section_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_section_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
+
+#[get("/get-all-sections", wrap = "Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Sections, Action::Read))")]
+pub async fn get_all_sections_paginate_controller(db: Data<DatabaseConnection>, query: Query<SectionQueryOptions>) -> HttpResponse {
+    // input validation
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match get_all_sections_paginate_service(&db, query.academic_year_id, query.grade_level, query.page, query.size).await {
+        Ok(sections) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All sections".to_string(),
+                data: sections,
+            };
+            info!("All sections: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all sections {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/{id}/students", wrap = "Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Students, Action::Read))")]
+pub async fn get_section_students_controller(db: Data<DatabaseConnection>, id: Path<String>, query: Query<TermQueryOptions>) -> HttpResponse {
+    match get_section_students_service(&db, id.to_string(), query.term_id).await {
+        Ok(students) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "section's students".to_string(),
+                data: students,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get section's students {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/{id}/students", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Sections, Action::Write))")]
+pub async fn move_students_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<MoveStudentsRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match move_students_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(students) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "students have moved".to_string(),
+                data: students,
+            };
+            info!("students moved to section {}: {:?}", id, res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("students not moved : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("/{id}/students/{student_id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Sections, Action::Write))")]
+pub async fn remove_student_from_section_controller(db: Data<DatabaseConnection>, path: Path<(String, String)>, query: Query<TermQueryOptions>, user: AuthenticatedUser) -> HttpResponse {
+    let (id, student_id) = path.into_inner();
+    match remove_student_from_section_service(&db, id, student_id, query.term_id, &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
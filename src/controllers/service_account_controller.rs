@@ -0,0 +1,124 @@
+use actix_web::{delete, get, post, HttpResponse};
+use actix_web::web::{Data, Json, Path};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::api_key_model::ApiKeyRequestDto;
+use crate::models::service_account_model::ServiceAccountRequestDto;
+use crate::services::service_account_service::{create_api_key_service, create_service_account_service, delete_service_account_service, get_all_service_accounts_service, get_api_keys_service, revoke_api_key_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Write))")]
+pub async fn create_service_account_controller(db: Data<DatabaseConnection>, dto: Json<ServiceAccountRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_service_account_service(&db, dto.into_inner(), &user).await {
+        Ok(account) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "service account has created".to_string(),
+                data: account,
+            };
+            info!("service account has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(e) => {
+            error!("service account not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/get-all-service-accounts", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Read))")]
+pub async fn get_all_service_accounts_controller(db: Data<DatabaseConnection>) -> HttpResponse {
+    match get_all_service_accounts_service(&db).await {
+        Ok(accounts) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All service accounts".to_string(),
+                data: accounts,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all service accounts {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Delete))")]
+pub async fn delete_service_account_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_service_account_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
+
+#[post("/{id}/keys/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Write))")]
+pub async fn create_api_key_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<ApiKeyRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_api_key_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(key) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "api key has created, store it now, it is not shown again".to_string(),
+                data: key,
+            };
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ForbiddenError(e)) => HttpResponse::Forbidden().body(SystemError::ForbiddenError(e).to_string()),
+        Err(e) => {
+            error!("api key not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/{id}/keys", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Read))")]
+pub async fn get_api_keys_controller(db: Data<DatabaseConnection>, id: Path<String>) -> HttpResponse {
+    match get_api_keys_service(&db, id.to_string()).await {
+        Ok(keys) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All api keys".to_string(),
+                data: keys,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all api keys {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/keys/revoke/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::ServiceAccounts, Action::Write))")]
+pub async fn revoke_api_key_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match revoke_api_key_service(&db, id.to_string(), &user).await {
+        Ok(key) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "api key has revoked".to_string(),
+                data: key,
+            };
+            info!("api key has revoked {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("api key not revoked : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use actix_web::{delete, get, HttpResponse};
+use actix_web::web::{Data, Path};
+use log::error;
+use sea_orm::DatabaseConnection;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::services::session_service::{get_my_sessions_service, get_user_sessions_service, revoke_my_session_service, revoke_user_session_service};
+use crate::utill::generic_response::GenericResponse;
+
+// the caller's own sessions, in the /me scope
+#[get("/sessions")]
+pub async fn get_my_sessions_controller(db: Data<DatabaseConnection>, user: AuthenticatedUser) -> HttpResponse {
+    match get_my_sessions_service(&db, &user).await {
+        Ok(sessions) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All sessions".to_string(),
+                data: sessions,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all sessions {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("/sessions/{id}")]
+pub async fn revoke_my_session_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match revoke_my_session_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(e) => {
+            error!("session not revoked : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// sessions of any user, in the /users scope
+#[get("/{id}/sessions", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Read))")]
+pub async fn get_user_sessions_controller(db: Data<DatabaseConnection>, id: Path<String>) -> HttpResponse {
+    match get_user_sessions_service(&db, id.to_string()).await {
+        Ok(sessions) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All sessions".to_string(),
+                data: sessions,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get all sessions {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("/{id}/sessions/{session_id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn revoke_user_session_controller(db: Data<DatabaseConnection>, path: Path<(String, String)>, user: AuthenticatedUser) -> HttpResponse {
+    let (id, session_id) = path.into_inner();
+
+    match revoke_user_session_service(&db, id, session_id, &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(e) => {
+            error!("session not revoked : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
@@ -1,25 +1,23 @@
-use actix_web::{delete, get, post, put, HttpRequest, HttpResponse};
+use actix_web::{delete, get, post, put, HttpResponse};
 use actix_web::web::{Data, Json, Path, Query};
 use log::{error, info};
 use sea_orm::DatabaseConnection;
 use validator::Validate;
-use crate::midleware::permission::{Permission, Role};
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
 use crate::models::student_model::{StudentQueryOptions, StudentRequestDto};
-use crate::services::student_service::{create_student_service, delete_student_service, get_all_students_paginate_service, update_student_service};
+use crate::services::student_service::{create_student_service, delete_student_service, get_all_students_paginate_service, get_student_terms_service, update_student_service};
 use crate::utill::generic_response::GenericResponse;
-use crate::utill::jwt::has_permission_with_roles;
-
-#[post("/create")]
-pub async fn create_student_controller(db: Data<DatabaseConnection>, dto: Json<StudentRequestDto>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin], &Permission::Write) {
-        return HttpResponse::Forbidden().body("You do not have permission to create student");
-    }
 
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Students, Action::Write))")]
+pub async fn create_student_controller(db: Data<DatabaseConnection>, dto: Json<StudentRequestDto>, user: AuthenticatedUser) -> HttpResponse {
     if let Err(e) = dto.validate() {
         return HttpResponse::BadRequest().body(e.to_string());
     }
 
-    match create_student_service(&db, dto.into_inner()).await {
+    match create_student_service(&db, dto.into_inner(), &user).await {
         Ok(student) => {
             let res = GenericResponse {
                 code: 201,
@@ -29,6 +27,7 @@ pub async fn create_student_controller(db: Data<DatabaseConnection>, dto: Json<S
             info!("student has created {:?}",res);
             HttpResponse::Created().json(res)
         }
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
         Err(e) => {
             error!("student not created : error :: {:?}",e);
             HttpResponse::InternalServerError().body(e.to_string())
@@ -36,18 +35,14 @@ pub async fn create_student_controller(db: Data<DatabaseConnection>, dto: Json<S
     }
 }
 
-#[put("/update/{id}")]
-pub async fn update_student_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<StudentRequestDto>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin], &Permission::Write) {
-        return HttpResponse::Forbidden().body("You do not have permission to update student");
-    }
-
+#[put("/update/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Students, Action::Write))")]
+pub async fn update_student_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<StudentRequestDto>, user: AuthenticatedUser) -> HttpResponse {
     // input validation
     if let Err(e) = dto.validate() {
         return HttpResponse::BadRequest().body(e.to_string());
     }
 
-    match update_student_service(&db, id.to_string(), dto.into_inner()).await {
+    match update_student_service(&db, id.to_string(), dto.into_inner(), &user).await {
         Ok(update_student) => {
             let res = GenericResponse {
                 code: 201,
@@ -57,7 +52,7 @@ pub async fn update_student_controller(db: Data<DatabaseConnection>, id: Path<St
             info!("Student successfully updated: {:?}", res);
             HttpResponse::Created().json(res)
         }
-
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
         Err(e) => {
             error!("Student not updated : error :: {:?}",e);
             HttpResponse::InternalServerError().body(e.to_string())
@@ -65,24 +60,16 @@ pub async fn update_student_controller(db: Data<DatabaseConnection>, id: Path<St
     }
 }
 
-#[delete("delete/{id}")]
-pub async fn delete_student_controller(db: Data<DatabaseConnection>, id: Path<String>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin], &Permission::Delete) {
-        return HttpResponse::Forbidden().body("You do not have permission to delete student");
-    }
-
-    match delete_student_service(&db, id.to_string()).await {
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Students, Action::Delete))")]
+pub async fn delete_student_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_student_service(&db, id.to_string(), &user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::InternalServerError().body(e.to_string())
     }
 }
 
-#[get("/get-all-students")]
-pub async fn get_all_students_paginate_controller(db: Data<DatabaseConnection>, query: Query<StudentQueryOptions>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin, Role::Student, Role::User], &Permission::Read) {
-        return HttpResponse::Forbidden().body("You do not have permission to get users");
-    }
-
+#[get("/get-all-students", wrap = "Authorize::new(&[Role::Admin, Role::Student, Role::Teacher, Role::User], Permission::new(Resource::Students, Action::Read)).with_custom_roles()")]
+pub async fn get_all_students_paginate_controller(db: Data<DatabaseConnection>, query: Query<StudentQueryOptions>) -> HttpResponse {
     // input validation
     if let Err(e) = query.validate() {
         return HttpResponse::BadRequest().body(e.to_string());
@@ -106,4 +93,24 @@ pub async fn get_all_students_paginate_controller(db: Data<DatabaseConnection>,
             HttpResponse::InternalServerError().body(e.to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+#[get("/{id}/terms", wrap = "Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Students, Action::Read))")]
+pub async fn get_student_terms_controller(db: Data<DatabaseConnection>, id: Path<String>) -> HttpResponse {
+    match get_student_terms_service(&db, id.to_string()).await {
+        Ok(terms) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "student's terms".to_string(),
+                data: terms,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get student's terms {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use actix_web::{delete, get, post, HttpResponse};
+use actix_web::web::{Data, Json, Path, Query};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::teacher_model::{TeacherQueryOptions, TeacherRequestDto};
+use crate::services::teacher_service::{create_teacher_service, delete_teacher_service, get_all_teachers_paginate_service, get_teacher_students_service, link_legacy_class_teachers_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Teachers, Action::Write))")]
+pub async fn create_teacher_controller(db: Data<DatabaseConnection>, dto: Json<TeacherRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_teacher_service(&db, dto.into_inner(), &user).await {
+        Ok(teacher) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "teacher has created".to_string(),
+                data: teacher,
+            };
+            info!("teacher has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("teacher not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/link-legacy-class-teachers", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Students, Action::Write))")]
+pub async fn link_legacy_class_teachers_controller(db: Data<DatabaseConnection>, user: AuthenticatedUser) -> HttpResponse {
+    match link_legacy_class_teachers_service(&db, &user).await {
+        Ok(result) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "legacy class teachers linked".to_string(),
+                data: result,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("legacy class teachers not linked : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Teachers, Action::Delete))")]
+pub async fn delete_teacher_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_teacher_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
+
+#[get("/get-all-teachers", wrap = "Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Teachers, Action::Read))")]
+pub async fn get_all_teachers_paginate_controller(db: Data<DatabaseConnection>, query: Query<TeacherQueryOptions>) -> HttpResponse {
+    // input validation
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    let search_text = query.search_text.clone().unwrap_or("".to_string());
+    match get_all_teachers_paginate_service(&db, search_text, query.page, query.size).await {
+        Ok(teachers) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All teachers".to_string(),
+                data: teachers,
+            };
+            info!("All teachers: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all teachers {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/{id}/students", wrap = "Authorize::new(&[Role::Admin, Role::Teacher], Permission::new(Resource::Students, Action::Read)).with_custom_roles()")]
+pub async fn get_teacher_students_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match get_teacher_students_service(&db, id.to_string(), &user).await {
+        Ok(students) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "teacher's students".to_string(),
+                data: students,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::ForbiddenError(e)) => HttpResponse::Forbidden().body(SystemError::ForbiddenError(e).to_string()),
+        Err(e) => {
+            error!("Failed get teacher's students {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use actix_web::{delete, get, post, put, HttpResponse};
+use actix_web::web::{Data, Json, Path};
+use log::{error, info};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::term_model::{TermRequestDto, TermResponseDto};
+use crate::services::term_service::{activate_term_service, create_term_service, delete_term_service, get_active_term_service, update_term_service};
+use crate::utill::generic_response::GenericResponse;
+
+#[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Write))")]
+pub async fn create_term_controller(db: Data<DatabaseConnection>, dto: Json<TermRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match create_term_service(&db, dto.into_inner(), &user).await {
+        Ok(term) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "term has created".to_string(),
+                data: term,
+            };
+            info!("term has created {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("term not created : error :: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[put("/update/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Write))")]
+pub async fn update_term_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<TermRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    term_response(update_term_service(&db, id.to_string(), dto.into_inner(), &user).await, "term has updated")
+}
+
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Delete))")]
+pub async fn delete_term_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_term_service(&db, id.to_string(), &user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string())
+    }
+}
+
+#[post("/{id}/activate", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Terms, Action::Write))")]
+pub async fn activate_term_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    term_response(activate_term_service(&db, id.to_string(), &user).await, "term is active")
+}
+
+#[get("/active")]
+pub async fn get_active_term_controller(db: Data<DatabaseConnection>) -> HttpResponse {
+    term_response(get_active_term_service(&db).await, "active term")
+}
+
+
+fn term_response(result: Result<TermResponseDto, SystemError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(term) => {
+            let res = GenericResponse {
+                code: 200,
+                message: message.to_string(),
+                data: term,
+            };
+            info!("{}: {:?}", message, res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("{} : error :: {:?}", message, e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use actix_web::{get, post, HttpResponse};
+use actix_web::web::{Data, Json};
+use log::{error, info, warn};
+use sea_orm::DatabaseConnection;
+use validator::Validate;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::refresh_token_model::{LogoutRequestDto, RefreshTokenRequestDto};
+use crate::services::token_service::{logout_service, refresh_token_service};
+use crate::utill::generic_response::GenericResponse;
+use crate::utill::jwt_keys::jwks;
+
+#[post("/token/refresh")]
+pub async fn refresh_token_controller(db: Data<DatabaseConnection>, dto: Json<RefreshTokenRequestDto>, client: ClientInfo) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match refresh_token_service(&db, dto.into_inner(), client).await {
+        Ok(tokens) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "token refreshed".to_string(),
+                data: tokens,
+            };
+            info!("token successfully refreshed");
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::JwtError(e)) => {
+            warn!("token refresh rejected : {:?}", e);
+            HttpResponse::Unauthorized().body(e.to_string())
+        }
+        Err(e) => {
+            error!("Failed to refresh token {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/logout")]
+pub async fn logout_controller(db: Data<DatabaseConnection>, dto: Option<Json<LogoutRequestDto>>, user: AuthenticatedUser) -> HttpResponse {
+    let dto = dto.map(|dto| dto.into_inner()).unwrap_or_default();
+
+    match logout_service(&db, &user, dto).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!("Failed to logout {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+// public signing keys, served in the standard JWKS format for other services
+#[get("/.well-known/jwks.json")]
+pub async fn jwks_controller() -> HttpResponse {
+    HttpResponse::Ok().json(jwks())
+}
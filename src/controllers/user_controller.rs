@@ -1,36 +1,52 @@
-use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
-use actix_web::body::MessageBody;
+use actix_web::{delete, get, post, put, HttpResponse};
 use actix_web::web::{Data, Json, Path, Query};
 use log::{error, info};
 use sea_orm::DatabaseConnection;
-use uuid::Uuid;
 use validator::Validate;
-use crate::midleware::permission::{Permission, Role};
-use crate::models::user_model::{LoginRequestDto, UserQueryOptions, UserRequestDto};
-use crate::services::user_service::{create_user_service, delete_user_service, get_all_paginate_service, update_user_service, user_login_service};
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::authorize::Authorize;
+use crate::midleware::client_info::ClientInfo;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::lockout_event_model::LockoutEventQueryOptions;
+use crate::models::user_role_model::AddUserRoleRequestDto;
+use crate::models::user_model::{ChangeRoleRequestDto, ImpersonateRequestDto, LoginRequestDto, LoginResponseDto, UserQueryOptions, UserRequestDto};
+use crate::services::login_throttle_service::{get_lockout_events_service, unlock_user_service};
+use crate::services::token_service::{impersonate_user_service, revoke_all_user_tokens_service};
+use crate::services::user_role_service::{add_user_role_service, get_user_roles_service, remove_user_role_service};
+use crate::services::user_service::{change_role_service, create_user_service, delete_user_service, get_all_paginate_service, update_user_service, user_login_service};
 use crate::utill::generic_response::GenericResponse;
-use crate::utill::jwt::{has_permission_with_roles};
 
 
 #[post("/login")]
-pub async fn user_login_controller(db: Data<DatabaseConnection>, dto: Json<LoginRequestDto>) -> HttpResponse {
+pub async fn user_login_controller(db: Data<DatabaseConnection>, dto: Json<LoginRequestDto>, client: ClientInfo) -> HttpResponse {
 
     //input validation
     if let Err(e) = dto.validate() {
         return HttpResponse::BadRequest().body(e.to_string());
     }
 
-    match user_login_service(&db, dto.into_inner()).await {
+    let email = dto.username.clone();
+    match user_login_service(&db, dto.into_inner(), client).await {
         Ok(Some(token)) => {
+            let message = match token {
+                LoginResponseDto::Tokens(_) => "successfully logged in",
+                LoginResponseDto::MfaRequired(_) => "two-factor code required",
+            };
+            // the response carries the refresh token or the challenge token, never log it
+            info!("User {} logged in: {}", email, message);
             let res = GenericResponse {
                 code: 200,
-                message: "successfully logged in".to_string(),
+                message: message.to_string(),
                 data: token,
             };
-            info!("User successfully logged in : {:?}", res);
             HttpResponse::Ok().json(res)
         }
         Ok(None) => HttpResponse::Unauthorized().body("Invalid credentials".to_string()),
+        Err(SystemError::EmailNotVerified) => HttpResponse::Forbidden().body(SystemError::EmailNotVerified.to_string()),
+        Err(SystemError::AccountLocked(retry_after)) => HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.to_string()))
+            .body(SystemError::AccountLocked(retry_after).to_string()),
         Err(e) => {
             error!("User failed successfully logged in {:?}",e);
             HttpResponse::InternalServerError().body(e.to_string())
@@ -55,6 +71,11 @@ pub async fn create_user_controller(db: Data<DatabaseConnection>, dto: Json<User
             info!("User successfully created : {:?}", res);
             HttpResponse::Created().json(res)
         }
+        Err(SystemError::FieldValidationError(errors)) => HttpResponse::BadRequest().json(GenericResponse {
+            code: 400,
+            message: "validation failed".to_string(),
+            data: errors,
+        }),
         Err(e) => {
             error!("User not created : error :: {:?}",e);
             HttpResponse::InternalServerError().body(e.to_string())
@@ -62,28 +83,29 @@ pub async fn create_user_controller(db: Data<DatabaseConnection>, dto: Json<User
     }
 }
 
-#[put("/update/{id}")]
-pub async fn update_user_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<UserRequestDto>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin], &Permission::Write) {
-        return HttpResponse::Forbidden().body("You do not have permission to update users");
-    }
-
+#[put("/update/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn update_user_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<UserRequestDto>, user: AuthenticatedUser) -> HttpResponse {
     // input validation
     if let Err(e) = dto.validate() {
         return HttpResponse::BadRequest().body(e.to_string());
     }
 
-    match update_user_service(&db, id.to_string(), dto.into_inner()).await {
-        Ok(user) => {
+    match update_user_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(updated_user) => {
             let res = GenericResponse {
                 code: 201,
                 message: "user has updated".to_string(),
-                data: user,
+                data: updated_user,
             };
             info!("User successfully updated: {:?}", res);
             HttpResponse::Created().json(res)
         }
 
+        Err(SystemError::FieldValidationError(errors)) => HttpResponse::BadRequest().json(GenericResponse {
+            code: 400,
+            message: "validation failed".to_string(),
+            data: errors,
+        }),
         Err(e) => {
             error!("User not updated : error :: {:?}",e);
             HttpResponse::InternalServerError().body(e.to_string())
@@ -91,24 +113,17 @@ pub async fn update_user_controller(db: Data<DatabaseConnection>, id: Path<Strin
     }
 }
 
-#[delete("delete/{id}")]
-pub async fn delete_user_controller(db: Data<DatabaseConnection>, id: Path<String>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin], &Permission::Delete) {
-        return HttpResponse::Forbidden().body("You do not have permission to delete users");
-    }
-
-    match delete_user_service(&db, id.to_string()).await {
+#[delete("delete/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Delete))")]
+pub async fn delete_user_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match delete_user_service(&db, id.to_string(), &user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
+        Err(SystemError::LastAdminError) => HttpResponse::Conflict().body(SystemError::LastAdminError.to_string()),
         Err(e) => HttpResponse::InternalServerError().body(e.to_string())
     }
 }
 
-#[get("/get-all-users")]
-pub async fn get_all_paginate_controller(db: Data<DatabaseConnection>, query: Query<UserQueryOptions>, req: HttpRequest) -> HttpResponse {
-    if !has_permission_with_roles(&req, &vec![Role::Admin], &Permission::Read) {
-        return HttpResponse::Forbidden().body("You do not have permission to get users");
-    }
-
+#[get("/get-all-users", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Read))")]
+pub async fn get_all_paginate_controller(db: Data<DatabaseConnection>, query: Query<UserQueryOptions>) -> HttpResponse {
     // input validation
     if let Err(e) = query.validate() {
         return HttpResponse::BadRequest().body(e.to_string());
@@ -134,4 +149,181 @@ pub async fn get_all_paginate_controller(db: Data<DatabaseConnection>, query: Qu
     }
 }
 
+#[post("/revoke-tokens/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn revoke_user_tokens_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match revoke_all_user_tokens_service(&db, id.to_string(), &user).await {
+        Ok(revoked_user) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "user tokens have been revoked".to_string(),
+                data: revoked_user,
+            };
+            info!("User tokens revoked: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("User tokens not revoked : error :: {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/unlock/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn unlock_user_controller(db: Data<DatabaseConnection>, id: Path<String>, user: AuthenticatedUser) -> HttpResponse {
+    match unlock_user_service(&db, id.to_string(), &user).await {
+        Ok(unlocked_user) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "user has unlocked".to_string(),
+                data: unlocked_user.email,
+            };
+            info!("User unlocked: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("User not unlocked : error :: {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/get-all-lockout-events", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Read))")]
+pub async fn get_all_lockout_events_controller(db: Data<DatabaseConnection>, query: Query<LockoutEventQueryOptions>) -> HttpResponse {
+    // input validation
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match get_lockout_events_service(&db, query.page, query.size).await {
+        Ok(events) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "All lockout events".to_string(),
+                data: events,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(e) => {
+            error!("Failed get all lockout events {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/impersonate/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn impersonate_user_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<ImpersonateRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match impersonate_user_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(token) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "impersonation token issued".to_string(),
+                data: token,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Impersonation not started : error :: {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[put("/change-role/{id}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn change_role_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<ChangeRoleRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    match change_role_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(updated_user) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "user role has changed".to_string(),
+                data: updated_user,
+            };
+            info!("User role changed: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::LastAdminError) => HttpResponse::Conflict().body(SystemError::LastAdminError.to_string()),
+        Err(e) => {
+            error!("User role not changed : error :: {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[get("/{id}/roles", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Read))")]
+pub async fn get_user_roles_controller(db: Data<DatabaseConnection>, id: Path<String>) -> HttpResponse {
+    match get_user_roles_service(&db, id.to_string()).await {
+        Ok(roles) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "user roles".to_string(),
+                data: roles,
+            };
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(e) => {
+            error!("Failed get user roles {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[post("/{id}/roles", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn add_user_role_controller(db: Data<DatabaseConnection>, id: Path<String>, dto: Json<AddUserRoleRequestDto>, user: AuthenticatedUser) -> HttpResponse {
+    // input validation
+    if let Err(e) = dto.validate() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+
+    match add_user_role_service(&db, id.to_string(), dto.into_inner(), &user).await {
+        Ok(roles) => {
+            let res = GenericResponse {
+                code: 201,
+                message: "role has added to the user".to_string(),
+                data: roles,
+            };
+            info!("User role added: {:?}", res);
+            HttpResponse::Created().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::DuplicateError(e)) => HttpResponse::Conflict().body(SystemError::DuplicateError(e).to_string()),
+        Err(e) => {
+            error!("User role not added : error :: {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+#[delete("/{id}/roles/{role}", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Write))")]
+pub async fn remove_user_role_controller(db: Data<DatabaseConnection>, path: Path<(String, String)>, user: AuthenticatedUser) -> HttpResponse {
+    let (id, role) = path.into_inner();
 
+    match remove_user_role_service(&db, id, role, &user).await {
+        Ok(roles) => {
+            let res = GenericResponse {
+                code: 200,
+                message: "role has removed from the user".to_string(),
+                data: roles,
+            };
+            info!("User role removed: {:?}", res);
+            HttpResponse::Ok().json(res)
+        }
+        Err(SystemError::ValidationError(e)) => HttpResponse::BadRequest().body(e),
+        Err(SystemError::NotFoundError(e)) => HttpResponse::NotFound().body(SystemError::NotFoundError(e).to_string()),
+        Err(SystemError::LastAdminError) => HttpResponse::Conflict().body(SystemError::LastAdminError.to_string()),
+        Err(e) => {
+            error!("User role not removed : error :: {:?}",e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
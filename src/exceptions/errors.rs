@@ -1,8 +1,9 @@
 use sea_orm::DbErr;
 use thiserror::Error;
+use validator::ValidationErrors;
 
 /// ---------  DB errors -------------------------------
-
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum SystemError {
     #[error("Database Error : {0}")]
@@ -11,17 +12,41 @@ pub enum SystemError {
     #[error("{0} already exists")]
     DuplicateError(String),
 
-    #[error("{0} invalid password, try again")]
+    #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("Validation error: {0}")]
+    FieldValidationError(ValidationErrors), // returned to the client per field
+
     #[error("{0} not found")]
     NotFoundError(String),
 
+    #[error("Forbidden: {0}")]
+    ForbiddenError(String),
+
     #[error("Password error: {0}")]
     PasswordError(#[from] PasswordError),
 
     #[error("JWT Error: {0}")]
     JwtError(#[from] JwtError),
+
+    #[error("Mail error: {0}")]
+    MailError(String),
+
+    #[error("Email address is not verified")]
+    EmailNotVerified,
+
+    #[error("Too many failed logins, try again in {0} seconds")]
+    AccountLocked(i64),
+
+    #[error("The last admin can't be demoted or deleted")]
+    LastAdminError,
+
+    #[error("Single sign-on failed: {0}")]
+    OidcError(String), // the sign-in is refused
+
+    #[error("Identity provider error: {0}")]
+    OidcProviderError(String), // the provider could not be reached or answered unexpectedly
 }
 
 
@@ -30,6 +55,14 @@ pub enum SystemError {
 pub enum JwtError {
     #[error("Invalid token: {0}")]
     TokenError(String),
+    #[error("Signing key error: {0}")]
+    KeyError(String),
+    #[error("Invalid refresh token")]
+    InvalidRefreshToken,
+    #[error("Refresh token has expired")]
+    ExpiredRefreshToken,
+    #[error("Refresh token has already been used, all sessions of this login are revoked")]
+    RefreshTokenReused,
 }
 
 /// ---------  Password errors -------------------------------
@@ -39,5 +72,9 @@ pub enum PasswordError {
     InvalidPassword,
     #[error("Password hashing failed, Error: {0}")]
     PasswordHashErr(String),
+    #[error("Invalid or expired password reset token")]
+    InvalidResetToken,
+    #[error("Invalid authentication code")]
+    InvalidMfaCode,
 }
 
@@ -7,12 +7,15 @@ mod midleware;
 mod exceptions;
 mod repo;
 
-use actix_web::{App, HttpServer, Responder};
+use actix_web::{App, HttpServer};
 use actix_web::web::{Data};
 use dotenv::dotenv;
 use crate::config::database::{establish_connection};
 use crate::config::app::{app_config};
 use crate::config::governor::rate_limiter;
+use crate::config::schema::create_tables;
+use crate::utill::jwt_keys::{init_keys, schedule_key_rotation};
+use crate::utill::mailer::init_mailer;
 use crate::midleware::auth::JwtMiddleware;
 use crate::midleware::cors::cors;
 use crate::midleware::loggers::logger;
@@ -26,8 +29,16 @@ async fn main() -> std::io::Result<()> {
 
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
+    // load JWT signing keys and rotate them in the background
+    init_keys();
+    actix_web::rt::spawn(schedule_key_rotation());
+
+    // fail on a bad mail configuration before serving requests
+    init_mailer();
+
     // Initialize db  connection
     let db = establish_connection().await;
+    create_tables(&db).await.expect("Failed to create database tables");
     let db_data =  Data::new(db);
 
     // start the HTTP server
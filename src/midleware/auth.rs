@@ -3,8 +3,14 @@ use std::task::{Context, Poll};
 use futures::future::{ok, LocalBoxFuture, Ready};
 use actix_web::body::{BoxBody, EitherBody};
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::{Error, HttpResponse};
-use crate::utill::jwt::extract_and_check_role_from_token;
+use actix_web::{Error, HttpMessage, HttpResponse};
+use actix_web::web::Data;
+use log::{info, warn};
+use sea_orm::DatabaseConnection;
+use crate::utill::jwt::authenticate_request;
+
+// paths that can be called without a bearer token
+const PUBLIC_PATHS: &[&str] = &["/signup", "/login", "/login/mfa", "/token/refresh", "/.well-known/jwks.json", "/password/forgot", "/password/reset", "/verify-email", "/verify-email/resend", "/oidc/login", "/oidc/callback"];
 
 pub struct JwtMiddleware;
 
@@ -49,12 +55,28 @@ where
 
         Box::pin(async move {
             // Exclude certain paths from middleware
-            if path == "/signup" || path == "/login" {
+            if PUBLIC_PATHS.contains(&path.as_str()) {
                 return srv.call(req).await;
             }
 
-            if extract_and_check_role_from_token(&req) {
-                return srv.call(req).await;
+            if let Some(db) = req.app_data::<Data<DatabaseConnection>>().cloned() {
+                if let Some(user) = authenticate_request(&req, &db).await {
+                    if let Some(impersonator) = &user.impersonator {
+                        info!("impersonated request: {} ({}) as {} ({}) : {} {}", impersonator.sub, impersonator.uid, user.email, user.user_id, req.method(), path);
+                    }
+                    // a read-only token can only look, and end itself
+                    if user.read_only && !req.method().is_safe() && path != "/logout" {
+                        warn!("read-only token of {} refused for {} {}", user.email, req.method(), path);
+                        let response = HttpResponse::Forbidden()
+                            .insert_header(("content-type", "text/plain"))
+                            .body("Forbidden: read-only token");
+                        let (req_parts, _) = req.into_parts();
+                        return Ok(ServiceResponse::new(req_parts, response.map_into_right_body()));
+                    }
+
+                    req.extensions_mut().insert(user);
+                    return srv.call(req).await;
+                }
             }
 
             // Unauthorized response for missing/invalid token
@@ -0,0 +1,103 @@
+use actix_web::dev::Payload;
+use actix_web::error::ErrorUnauthorized;
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{err, ok, Ready};
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+use crate::midleware::permission::{has_permission, Action, Permission, Resource, Role};
+use crate::models::{api_key_model, service_account_model};
+use crate::utill::jwt::{Claims, Impersonator};
+
+// the caller of a request, put into the request extensions by JwtMiddleware
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user_id: Uuid,
+    pub email: String,
+    pub roles: Vec<Role>,
+    pub token_id: String,
+    pub expires_at: usize,
+    pub restricted: bool,
+    pub session_id: Option<Uuid>,
+    pub impersonator: Option<Impersonator>,
+    pub read_only: bool,
+    // set for API keys, which carry their own grants instead of roles
+    pub api_key_permissions: Option<Vec<String>>,
+}
+
+impl AuthenticatedUser {
+    pub fn from_claims(claims: Claims) -> Self {
+        Self {
+            user_id: claims.uid,
+            email: claims.sub,
+            roles: claims.roles.iter().filter_map(|role| Role::from_str(role)).collect(),
+            token_id: claims.jti,
+            expires_at: claims.exp,
+            restricted: claims.restricted,
+            session_id: claims.sid,
+            impersonator: claims.act,
+            read_only: claims.read_only,
+            api_key_permissions: None,
+        }
+    }
+
+    // the caller behind an API key is the service account that owns it
+    pub fn from_api_key(account: &service_account_model::Model, key: &api_key_model::Model, permissions: Vec<String>) -> Self {
+        Self {
+            user_id: account.id,
+            email: format!("service:{}", account.name),
+            roles: vec![],
+            token_id: key.id.to_string(),
+            expires_at: key.expires_at.timestamp() as usize,
+            restricted: false,
+            session_id: None,
+            impersonator: None,
+            read_only: false,
+            api_key_permissions: Some(permissions),
+        }
+    }
+
+    // Check if the user has one of the roles and that role grants the permission on the resource,
+    // custom roles aren't named on routes, they only count where the route opts them in
+    pub async fn has_permission_with_roles(&self, db: &DatabaseConnection, roles: &[Role], permission: &Permission, custom_roles: bool) -> bool {
+        if self.restricted || (self.read_only && permission.action != Action::Read) {
+            return false;
+        }
+        if let Some(granted) = &self.api_key_permissions {
+            // keys never manage service accounts or keys, not even with *:*, else one key could mint the next
+            return permission.resource != Resource::ServiceAccounts && granted.iter().any(|name| permission.is_granted_by(name));
+        }
+        for role in self.roles.iter().filter(|role| roles.contains(role) || (custom_roles && matches!(role, Role::Custom(_)))) {
+            if has_permission(db, role, permission).await {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl AuthenticatedUser {
+    // whether any of the caller's own grants covers the permission, regardless of the route
+    pub async fn holds_permission(&self, db: &DatabaseConnection, permission: &Permission) -> bool {
+        if let Some(granted) = &self.api_key_permissions {
+            return granted.iter().any(|name| permission.is_granted_by(name));
+        }
+        for role in &self.roles {
+            if has_permission(db, role, permission).await {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        match req.extensions().get::<AuthenticatedUser>() {
+            Some(user) => ok(user.clone()),
+            None => err(ErrorUnauthorized("Unauthorized: Missing or invalid token")),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use futures::future::{ok, LocalBoxFuture, Ready};
+use actix_web::body::EitherBody;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{Error, HttpMessage, HttpResponse};
+use actix_web::web::Data;
+use log::warn;
+use sea_orm::DatabaseConnection;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::{Permission, Role};
+
+/// Route guard, rejects the request with 403 before the handler runs when the
+/// caller has none of the roles or those roles don't grant the permission.
+///
+/// on a route:  #[post("/create", wrap = "Authorize::new(&[Role::Admin], Permission::new(Resource::Students, Action::Write))")]
+/// on a scope:  scope("/users").wrap(Authorize::new(&[Role::Admin], Permission::new(Resource::Users, Action::Read)))
+///
+/// custom roles are only let through on routes that opt them in with `with_custom_roles()`,
+/// never do that on admin-only routes
+pub struct Authorize {
+    roles: Rc<Vec<Role>>,
+    permission: Rc<Permission>,
+    custom_roles: bool,
+}
+
+impl Authorize {
+    pub fn new(roles: &[Role], permission: Permission) -> Self {
+        Self {
+            roles: Rc::new(roles.to_vec()),
+            permission: Rc::new(permission),
+            custom_roles: false,
+        }
+    }
+
+    // custom roles pass on their granted permissions
+    pub fn with_custom_roles(mut self) -> Self {
+        self.custom_roles = true;
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Authorize
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = AuthorizeService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(AuthorizeService {
+            service: Rc::new(service),
+            roles: self.roles.clone(),
+            permission: self.permission.clone(),
+            custom_roles: self.custom_roles,
+        })
+    }
+}
+
+pub struct AuthorizeService<S> {
+    service: Rc<S>,
+    roles: Rc<Vec<Role>>,
+    permission: Rc<Permission>,
+    custom_roles: bool,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthorizeService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let roles = self.roles.clone();
+        let permission = self.permission.clone();
+        let custom_roles = self.custom_roles;
+
+        Box::pin(async move {
+            // JwtMiddleware puts the caller into the request extensions
+            let user = req.extensions().get::<AuthenticatedUser>().cloned();
+            let db = req.app_data::<Data<DatabaseConnection>>().cloned();
+
+            let response = match (user, db) {
+                (Some(user), Some(db)) if user.has_permission_with_roles(&db, &roles, &permission, custom_roles).await => {
+                    return srv.call(req).await.map(ServiceResponse::map_into_left_body);
+                }
+                (Some(user), _) => {
+                    warn!("access denied for {} to {} {}", user.email, req.method(), req.path());
+                    HttpResponse::Forbidden()
+                        .insert_header(("content-type", "text/plain"))
+                        .body("Forbidden: You do not have permission to access this resource")
+                }
+                (None, _) => HttpResponse::Unauthorized()
+                    .insert_header(("content-type", "text/plain"))
+                    .body("Unauthorized: Missing or invalid token"),
+            };
+
+            let (req_parts, _) = req.into_parts();
+            Ok(ServiceResponse::new(req_parts, response.map_into_right_body()))
+        })
+    }
+}
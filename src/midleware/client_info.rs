@@ -0,0 +1,43 @@
+use std::env;
+use std::net::IpAddr;
+use std::sync::LazyLock;
+use actix_web::dev::Payload;
+use actix_web::{Error, FromRequest, HttpRequest};
+use futures::future::{ok, Ready};
+
+// where a request comes from, recorded with logins and failed attempts
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// TRUSTED_PROXIES, comma separated proxy addresses whose Forwarded / X-Forwarded-For headers are believed,
+// anyone else could put any address in them
+static TRUSTED_PROXIES: LazyLock<Vec<IpAddr>> = LazyLock::new(|| {
+    env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|proxy| proxy.trim().parse().ok())
+        .collect()
+});
+
+impl FromRequest for ClientInfo {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let peer_ip = req.peer_addr().map(|addr| addr.ip());
+        let ip_address = match peer_ip {
+            Some(ip) if TRUSTED_PROXIES.contains(&ip) => req.connection_info().realip_remote_addr().map(|ip| ip.to_string()),
+            _ => peer_ip.map(|ip| ip.to_string()),
+        };
+        ok(ClientInfo {
+            ip_address,
+            user_agent: req.headers()
+                .get("User-Agent")
+                .and_then(|user_agent| user_agent.to_str().ok())
+                .map(|user_agent| user_agent.chars().take(512).collect()),
+        })
+    }
+}
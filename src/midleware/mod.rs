@@ -1,4 +1,7 @@
 pub mod auth;
+pub mod authenticated_user;
+pub mod client_info;
+pub mod authorize;
 pub mod permission;
 pub mod cors;
 pub mod security_headers;
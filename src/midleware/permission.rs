@@ -1,48 +1,248 @@
-use std::collections::HashMap;
+use log::error;
+use sea_orm::DatabaseConnection;
+use crate::services::role_service::role_permissions;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+const WILDCARD: &str = "*";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Role {
     Admin,
     User,
     Student,
+    Teacher,
+    Custom(String), // created by admins at runtime
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Users,
+    Students,
+    Roles,
+    Courses,
+    ServiceAccounts,
+    Teachers,
+    Sections,
+    Terms, // academic years and their terms
+    Enrollments, // a student's own enrollments, managing them for others is courses:write
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-pub enum Permission {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
     Read,
     Write,
     Delete,
 }
 
+// an action on a resource type, stored as "resource:action", e.g. students:write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Permission {
+    pub resource: Resource,
+    pub action: Action,
+}
+
 impl Role {
     pub fn from_str(role: &str) -> Option<Role> {
         match role {
             "Admin" => Some(Role::Admin),
             "User" => Some(Role::User),
-            "Guest" => Some(Role::Student),
-            _ => None, // Unknown role
+            "Student" => Some(Role::Student),
+            "Teacher" => Some(Role::Teacher),
+            "Guest" => Some(Role::Student), // name of the student role before it was renamed, still in older tokens
+            "" => None,
+            custom => Some(Role::Custom(custom.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Role::Admin => "Admin",
+            Role::User => "User",
+            Role::Student => "Student",
+            Role::Teacher => "Teacher",
+            Role::Custom(name) => name,
         }
     }
 }
 
+impl Resource {
+    pub fn all() -> Vec<Resource> {
+        vec![Resource::Users, Resource::Students, Resource::Roles, Resource::Courses, Resource::ServiceAccounts, Resource::Teachers, Resource::Sections, Resource::Terms, Resource::Enrollments]
+    }
 
-//get role permission
-fn get_role_permission() -> HashMap<Role, Vec<Permission>> {
-    let mut role_permission = HashMap::new();
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resource::Users => "users",
+            Resource::Students => "students",
+            Resource::Roles => "roles",
+            Resource::Courses => "courses",
+            Resource::ServiceAccounts => "service_accounts",
+            Resource::Teachers => "teachers",
+            Resource::Sections => "sections",
+            Resource::Terms => "terms",
+            Resource::Enrollments => "enrollments",
+        }
+    }
+}
 
-    role_permission.insert(Role::Admin, vec![Permission::Write, Permission::Read, Permission::Delete]);
-    role_permission.insert(Role::User, vec![Permission::Write, Permission::Read]);
-    role_permission.insert(Role::Student, vec![Permission::Read]);
+impl Action {
+    pub fn all() -> Vec<Action> {
+        vec![Action::Read, Action::Write, Action::Delete]
+    }
 
-    role_permission
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Read => "read",
+            Action::Write => "write",
+            Action::Delete => "delete",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Action::Read => "Read",
+            Action::Write => "Create and update",
+            Action::Delete => "Delete",
+        }
+    }
 }
 
+impl Permission {
+    pub fn new(resource: Resource, action: Action) -> Self {
+        Self { resource, action }
+    }
 
-pub fn has_permission(role: &Role, permission: &Permission) -> bool {
-    let role_permission = get_role_permission();
-    if let Some(permissions) = role_permission.get(role) {
-        permissions.contains(&permission)
-    } else {
-        false
+    pub fn name(&self) -> String {
+        format!("{}:{}", self.resource.as_str(), self.action.as_str())
     }
-}
\ No newline at end of file
+
+    // a granted permission matches when both parts are equal or "*", e.g. *:read or students:*
+    pub fn is_granted_by(&self, granted: &str) -> bool {
+        match granted.split_once(':') {
+            Some((resource, action)) => {
+                (resource == WILDCARD || resource == self.resource.as_str())
+                    && (action == WILDCARD || action == self.action.as_str())
+            }
+            None => false,
+        }
+    }
+}
+
+// the concrete permissions a granted name covers, e.g. students:* covers students:read, students:write and students:delete
+pub fn permissions_granted_by(granted: &str) -> Vec<Permission> {
+    Resource::all()
+        .into_iter()
+        .flat_map(|resource| Action::all().into_iter().map(move |action| Permission::new(resource, action)))
+        .filter(|permission| permission.is_granted_by(granted))
+        .collect()
+}
+
+// every grantable permission name with its description, wildcards included
+pub fn all_permissions() -> Vec<(String, String)> {
+    let mut resources: Vec<(&str, &str)> = Resource::all()
+        .into_iter()
+        .map(|resource| (resource.as_str(), resource.as_str()))
+        .collect();
+    resources.push((WILDCARD, "all resources"));
+
+    let mut permissions = Vec::new();
+    for (resource, resource_description) in &resources {
+        for action in Action::all() {
+            permissions.push((
+                format!("{}:{}", resource, action.as_str()),
+                format!("{} {}", action.description(), resource_description),
+            ));
+        }
+        permissions.push((
+            format!("{}:{}", resource, WILDCARD),
+            format!("Any action on {}", resource_description),
+        ));
+    }
+    permissions
+}
+
+
+// built-in role permissions, seeded into the database on first start
+pub fn default_role_permissions() -> Vec<(Role, Vec<String>)> {
+    vec![
+        (Role::Admin, vec![format!("{}:{}", WILDCARD, WILDCARD)]),
+        (Role::User, vec![Permission::new(Resource::Students, Action::Read).name()]),
+        (Role::Student, vec![
+            Permission::new(Resource::Students, Action::Read).name(),
+            Permission::new(Resource::Courses, Action::Read).name(),
+            Permission::new(Resource::Terms, Action::Read).name(),
+            Permission::new(Resource::Enrollments, Action::Write).name(),
+        ]),
+        (Role::Teacher, vec![
+            Permission::new(Resource::Students, Action::Read).name(),
+            Permission::new(Resource::Courses, Action::Read).name(),
+            Permission::new(Resource::Teachers, Action::Read).name(),
+            Permission::new(Resource::Sections, Action::Read).name(),
+            Permission::new(Resource::Terms, Action::Read).name(),
+        ]),
+    ]
+}
+
+
+pub async fn has_permission(db: &DatabaseConnection, role: &Role, permission: &Permission) -> bool {
+    match role_permissions(db).await {
+        Ok(role_permission) => role_permission
+            .get(role.as_str())
+            .is_some_and(|permissions| permissions.iter().any(|granted| permission.is_granted_by(granted))),
+        Err(e) => {
+            error!("failed to load role permissions: {:?}", e);
+            false
+        }
+    }
+}
+
+// a role is known when it exists in the roles table
+pub async fn is_known_role(db: &DatabaseConnection, role: &Role) -> bool {
+    match role_permissions(db).await {
+        Ok(role_permission) => role_permission.contains_key(role.as_str()),
+        Err(e) => {
+            error!("failed to load role permissions: {:?}", e);
+            false
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_permission_is_granted() {
+        let permission = Permission::new(Resource::Students, Action::Read);
+        assert!(permission.is_granted_by("students:read"));
+        assert!(!permission.is_granted_by("students:write"));
+        assert!(!permission.is_granted_by("courses:read"));
+    }
+
+    #[test]
+    fn wildcards_match_either_part() {
+        let permission = Permission::new(Resource::ServiceAccounts, Action::Delete);
+        assert!(permission.is_granted_by("service_accounts:*"));
+        assert!(permission.is_granted_by("*:delete"));
+        assert!(permission.is_granted_by("*:*"));
+        assert!(!permission.is_granted_by("*:read"));
+        assert!(!permission.is_granted_by("users:*"));
+    }
+
+    #[test]
+    fn wildcard_grants_expand_to_concrete_permissions() {
+        assert_eq!(permissions_granted_by("students:read"), [Permission::new(Resource::Students, Action::Read)]);
+        assert_eq!(permissions_granted_by("*:write").len(), Resource::all().len());
+        assert_eq!(permissions_granted_by("*:*").len(), Resource::all().len() * Action::all().len());
+        assert!(permissions_granted_by("students").is_empty());
+    }
+
+    #[test]
+    fn malformed_grants_match_nothing() {
+        let permission = Permission::new(Resource::Users, Action::Read);
+        assert!(!permission.is_granted_by("*"));
+        assert!(!permission.is_granted_by(""));
+        assert!(!permission.is_granted_by("users"));
+        assert!(!permission.is_granted_by("users:read:extra"));
+    }
+}
@@ -0,0 +1,57 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::utill::validator::custom_academic_year_check;
+
+// a school year, e.g. 2026-2027, split into terms
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "academic_years")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub name: String, // e.g. 2026-2027
+    pub start_date: Date,
+    pub end_date: Date,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::term_model::Entity")]
+    Terms,
+    #[sea_orm(has_many = "super::section_model::Entity")]
+    Sections,
+}
+
+impl Related<super::term_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Terms.def()
+    }
+}
+
+impl Related<super::section_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sections.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AcademicYearRequestDto {
+    #[validate(custom = "custom_academic_year_check")]
+    pub name: String,
+    pub start_date: Date,
+    pub end_date: Date,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AcademicYearResponseDto {
+    pub id: Uuid,
+    pub name: String,
+    pub start_date: Date,
+    pub end_date: Date,
+    pub created_at: DateTimeUtc,
+}
@@ -0,0 +1,76 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub service_account_id: Uuid,
+    pub name: String,
+    pub prefix: String, // first characters of the key, to recognise it in lists and logs
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+    pub last_used_at: Option<DateTimeUtc>,
+    pub revoked_at: Option<DateTimeUtc>,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::service_account_model::Entity",
+        from = "Column::ServiceAccountId",
+        to = "super::service_account_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ServiceAccount,
+    #[sea_orm(has_many = "super::api_key_permission_model::Entity")]
+    ApiKeyPermissions,
+}
+
+impl Related<super::service_account_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServiceAccount.def()
+    }
+}
+
+impl Related<super::api_key_permission_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiKeyPermissions.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ApiKeyRequestDto {
+    #[validate(length(min = 3, max = 50, message = "Name must be between 3 and 50 characters long"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one permission is required"))]
+    pub permissions: Vec<String>,
+    #[validate(range(min = 1, max = 365, message = "Expiry must be between 1 and 365 days"))]
+    pub expires_in_days: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiKeyResponseDto {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub permissions: Vec<String>,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+    pub last_used_at: Option<DateTimeUtc>,
+    pub revoked_at: Option<DateTimeUtc>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatedApiKeyResponseDto {
+    pub api_key: String, // shown once, only the hash is stored
+    pub key: ApiKeyResponseDto,
+}
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_key_permissions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub api_key_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub permission_name: String,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::api_key_model::Entity",
+        from = "Column::ApiKeyId",
+        to = "super::api_key_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ApiKey,
+    #[sea_orm(
+        belongs_to = "super::permission_model::Entity",
+        from = "Column::PermissionName",
+        to = "super::permission_model::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Permission,
+}
+
+impl Related<super::api_key_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiKey.def()
+    }
+}
+
+impl Related<super::permission_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Permission.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,110 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::utill::validator::custom_code_check;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "courses")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub code: String, // e.g. MATH-101, stored upper case
+    pub title: String,
+    pub description: String,
+    pub credits: i32,
+    pub owner_id: Option<Uuid>, // teacher in charge, the course stays when the account is deleted
+    pub capacity: Option<i32>, // enrolled seats, further students are waitlisted, unset is unlimited
+    pub status: CourseStatus,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+// the course_status database enum, only published courses are open to students
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "course_status")]
+pub enum CourseStatus {
+    #[sea_orm(string_value = "Draft")]
+    Draft,
+    #[sea_orm(string_value = "Published")]
+    Published,
+    #[sea_orm(string_value = "Archived")]
+    Archived,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user_model::Entity",
+        from = "Column::OwnerId",
+        to = "super::user_model::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Owner,
+    #[sea_orm(has_many = "super::enrollment_model::Entity")]
+    Enrollments,
+}
+
+impl Related<super::user_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Owner.def()
+    }
+}
+
+impl Related<super::enrollment_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Enrollments.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CourseRequestDto {
+    #[validate(length(min = 2, max = 20, message = "Code must be between 2 and 20 characters long"), custom = "custom_code_check")]
+    pub code: String,
+    #[validate(length(min = 3, max = 150, message = "Title must be between 3 and 150 characters long"))]
+    pub title: String,
+    #[validate(length(max = 2000, message = "Description must be at most 2000 characters long"))]
+    #[serde(default)]
+    pub description: String,
+    #[validate(range(min = 0, max = 60, message = "Credits must be between 0 and 60"))]
+    pub credits: i32,
+    #[validate(email(message = "Invalid email format"))]
+    pub owner_email: Option<String>,
+    #[validate(range(min = 1, message = "Capacity must be at least 1"))]
+    pub capacity: Option<i32>,
+    pub status: Option<CourseStatus>, // new courses start as drafts
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CourseQueryOptions {
+    #[validate(custom = "custom_code_check")]
+    pub search_text: Option<String>,
+    pub status: Option<CourseStatus>,
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: u64,
+    #[validate(range(min = 1, max = 100, message = "Size must be between 1 and 100"))]
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaginateCourseResponseDto {
+    pub count: u64,
+    pub list: Vec<CourseResponseDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CourseResponseDto {
+    pub id: Uuid,
+    pub code: String,
+    pub title: String,
+    pub description: String,
+    pub credits: i32,
+    pub owner_id: Option<Uuid>,
+    pub capacity: Option<i32>,
+    pub status: CourseStatus,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
@@ -0,0 +1,48 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "email_verification_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[sea_orm(unique)]
+    pub token_hash: String,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+    pub used_at: Option<DateTimeUtc>,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::user_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct VerifyEmailQuery {
+    #[validate(length(min = 1, message = "Verification token is required"))]
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ResendVerificationRequestDto {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
@@ -0,0 +1,135 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// a student's place in a course for one term, one row per course, student and term, a new enrollment reuses a dropped row
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "enrollments")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub course_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub student_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub term_id: Uuid,
+    pub status: EnrollmentStatus,
+    pub created_at: DateTimeUtc,
+    pub status_changed_at: DateTimeUtc, // waitlisted students are promoted in this order
+}
+
+// the enrollment_status database enum
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "enrollment_status")]
+pub enum EnrollmentStatus {
+    #[sea_orm(string_value = "Enrolled")]
+    Enrolled,
+    #[sea_orm(string_value = "Waitlisted")]
+    Waitlisted,
+    #[sea_orm(string_value = "Dropped")]
+    Dropped,
+    #[sea_orm(string_value = "Completed")]
+    Completed,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::course_model::Entity",
+        from = "Column::CourseId",
+        to = "super::course_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Course,
+    #[sea_orm(
+        belongs_to = "super::student_model::Entity",
+        from = "Column::StudentId",
+        to = "super::student_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Student,
+    #[sea_orm(
+        belongs_to = "super::term_model::Entity",
+        from = "Column::TermId",
+        to = "super::term_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Term,
+}
+
+impl Related<super::course_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Course.def()
+    }
+}
+
+impl Related<super::student_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Student.def()
+    }
+}
+
+impl Related<super::term_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Term.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EnrollRequestDto {
+    pub student_id: Uuid,
+}
+
+// admins drop or complete an enrollment, seats are only handed out by enrolling
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EnrollmentStatusRequestDto {
+    pub status: EnrollmentStatus,
+    #[serde(default)]
+    pub term_id: Option<Uuid>, // the active term when missing, past terms can still be completed
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EnrollmentResponseDto {
+    pub course_id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub status: EnrollmentStatus,
+    pub waitlist_position: Option<u64>,
+    pub created_at: DateTimeUtc,
+    pub status_changed_at: DateTimeUtc,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RosterEntryDto {
+    pub student_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub grade: String,
+    pub status: EnrollmentStatus,
+    pub status_changed_at: DateTimeUtc,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RosterResponseDto {
+    pub course_id: Uuid,
+    pub term_id: Uuid,
+    pub capacity: Option<i32>,
+    pub enrolled: Vec<RosterEntryDto>,
+    pub waitlisted: Vec<RosterEntryDto>, // in promotion order
+    pub completed: Vec<RosterEntryDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MyCourseDto {
+    pub course_id: Uuid,
+    pub term_id: Uuid,
+    pub code: String,
+    pub title: String,
+    pub credits: i32,
+    pub status: EnrollmentStatus,
+    pub waitlist_position: Option<u64>,
+    pub status_changed_at: DateTimeUtc,
+}
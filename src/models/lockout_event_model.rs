@@ -0,0 +1,52 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "lockout_events")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub event: String, // locked or unlocked
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTimeUtc>,
+    pub ip_address: Option<String>, // address of the last failed attempt
+    pub actor: Option<String>, // admin who unlocked the account
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::user_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct LockoutEventQueryOptions {
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: u64,
+    #[validate(range(min = 1, max = 100, message = "Size must be between 1 and 100"))]
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaginateLockoutEventResponseDto {
+    pub count: u64,
+    pub list: Vec<Model>,
+}
@@ -1,5 +1,49 @@
 pub mod user_model;
 pub mod student_model;
+pub mod refresh_token_model;
+pub mod revoked_token_model;
+pub mod role_model;
+pub mod permission_model;
+pub mod role_permission_model;
+pub mod password_reset_token_model;
+pub mod email_verification_token_model;
+pub mod lockout_event_model;
+pub mod recovery_code_model;
+pub mod service_account_model;
+pub mod api_key_model;
+pub mod api_key_permission_model;
+pub mod oidc_login_state_model;
+pub mod session_model;
+pub mod user_role_model;
+pub mod course_model;
+pub mod enrollment_model;
+pub mod teacher_model;
+pub mod section_model;
+pub mod academic_year_model;
+pub mod term_model;
+pub mod student_term_model;
 
 pub use user_model::Entity as User;
-pub use student_model::Entity as Student;
\ No newline at end of file
+pub use student_model::Entity as Student;
+pub use refresh_token_model::Entity as RefreshToken;
+pub use revoked_token_model::Entity as RevokedToken;
+pub use role_model::Entity as RoleEntity;
+pub use permission_model::Entity as PermissionEntity;
+pub use role_permission_model::Entity as RolePermission;
+pub use password_reset_token_model::Entity as PasswordResetToken;
+pub use email_verification_token_model::Entity as EmailVerificationToken;
+pub use lockout_event_model::Entity as LockoutEvent;
+pub use recovery_code_model::Entity as RecoveryCode;
+pub use service_account_model::Entity as ServiceAccount;
+pub use api_key_model::Entity as ApiKey;
+pub use api_key_permission_model::Entity as ApiKeyPermission;
+pub use oidc_login_state_model::Entity as OidcLoginState;
+pub use session_model::Entity as Session;
+pub use user_role_model::Entity as UserRoleEntity;
+pub use course_model::Entity as Course;
+pub use enrollment_model::Entity as Enrollment;
+pub use teacher_model::Entity as Teacher;
+pub use section_model::Entity as Section;
+pub use academic_year_model::Entity as AcademicYear;
+pub use term_model::Entity as Term;
+pub use student_term_model::Entity as StudentTerm;
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// one started OIDC sign-in, the state sent to the provider identifies it on the way back
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "oidc_login_states")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub state_hash: String,
+    pub code_verifier: String, // PKCE verifier, only its challenge leaves the server
+    pub nonce: String,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+    pub used_at: Option<DateTimeUtc>,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+// query of the redirect back from the identity provider
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct OidcCallbackQuery {
+    #[validate(length(min = 1, message = "State is required"))]
+    pub state: String,
+    pub code: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
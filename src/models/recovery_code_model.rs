@@ -0,0 +1,66 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "recovery_codes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub created_at: DateTimeUtc,
+    pub used_at: Option<DateTimeUtc>,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::user_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MfaEnrollResponseDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MfaConfirmRequestDto {
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecoveryCodesResponseDto {
+    pub recovery_codes: Vec<String>, // shown once, only hashes are stored
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MfaLoginRequestDto {
+    #[validate(length(min = 1, message = "MFA token is required"))]
+    pub mfa_token: String,
+    #[validate(length(min = 6, max = 11, message = "Code must be a 6 digit code or a recovery code"))]
+    pub code: String, // authenticator code or recovery code
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MfaChallengeResponseDto {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub expires_in: i64,
+}
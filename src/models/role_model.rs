@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::utill::validator::custom_text_check;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "roles")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    pub description: String,
+    pub built_in: bool, // built-in roles are used by the code and can't be removed
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::role_permission_model::Entity")]
+    RolePermissions,
+}
+
+impl Related<super::role_permission_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RolePermissions.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RoleRequestDto {
+    #[validate(length(min = 3, max = 50, message = "Name must be between 3 and 50 characters long"), custom = "custom_text_check")]
+    pub name: String,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters long"))]
+    pub description: String,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RoleResponseDto {
+    pub name: String,
+    pub description: String,
+    pub built_in: bool,
+    pub permissions: Vec<String>,
+}
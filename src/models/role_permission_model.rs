@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "role_permissions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub role_name: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub permission_name: String,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::role_model::Entity",
+        from = "Column::RoleName",
+        to = "super::role_model::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Role,
+    #[sea_orm(
+        belongs_to = "super::permission_model::Entity",
+        from = "Column::PermissionName",
+        to = "super::permission_model::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Permission,
+}
+
+impl Related<super::role_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Role.def()
+    }
+}
+
+impl Related<super::permission_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Permission.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,118 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::utill::validator::custom_section_letter_check;
+
+// a class of one grade in one academic year, e.g. grade 5 section B of 2026-2027
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sections")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub grade_level: i32,
+    pub section_letter: String, // stored upper case
+    pub academic_year_id: Uuid,
+    pub homeroom_teacher_id: Option<Uuid>,
+    pub room: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::teacher_model::Entity",
+        from = "Column::HomeroomTeacherId",
+        to = "super::teacher_model::Column::Id",
+        on_delete = "SetNull"
+    )]
+    HomeroomTeacher,
+    #[sea_orm(
+        belongs_to = "super::academic_year_model::Entity",
+        from = "Column::AcademicYearId",
+        to = "super::academic_year_model::Column::Id"
+    )]
+    AcademicYear,
+    #[sea_orm(has_many = "super::student_model::Entity")]
+    Students,
+}
+
+impl Related<super::teacher_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HomeroomTeacher.def()
+    }
+}
+
+impl Related<super::academic_year_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AcademicYear.def()
+    }
+}
+
+impl Related<super::student_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Students.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SectionRequestDto {
+    #[validate(range(min = 1, max = 13, message = "Grade level must be between 1 and 13"))]
+    pub grade_level: i32,
+    #[validate(custom = "custom_section_letter_check")]
+    pub section_letter: String,
+    pub academic_year_id: Uuid,
+    pub homeroom_teacher_id: Option<Uuid>,
+    #[validate(length(min = 1, max = 50, message = "Room must be between 1 and 50 characters long"))]
+    pub room: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SectionQueryOptions {
+    pub academic_year_id: Option<Uuid>,
+    #[validate(range(min = 1, max = 13, message = "Grade level must be between 1 and 13"))]
+    pub grade_level: Option<i32>,
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: u64,
+    #[validate(range(min = 1, max = 100, message = "Size must be between 1 and 100"))]
+    pub size: u64,
+}
+
+// students moved into the section from wherever they are in the term
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MoveStudentsRequestDto {
+    #[validate(length(min = 1, max = 100, message = "Between 1 and 100 students can be moved at once"))]
+    pub student_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub term_id: Option<Uuid>, // the active term when missing
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaginateSectionResponseDto {
+    pub count: u64,
+    pub list: Vec<SectionResponseDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SectionResponseDto {
+    pub id: Uuid,
+    pub name: String, // e.g. 5B
+    pub grade_level: i32,
+    pub section_letter: String,
+    pub academic_year_id: Uuid,
+    pub academic_year: String, // name of the year, e.g. 2026-2027
+    pub homeroom_teacher_id: Option<Uuid>,
+    pub room: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SectionStudentDto {
+    pub student_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub class_teacher_id: Option<Uuid>,
+}
@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// non-human caller, e.g. the nightly roster sync, authenticates with API keys only
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "service_accounts")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub name: String,
+    pub description: String,
+    pub created_by: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::api_key_model::Entity")]
+    ApiKeys,
+}
+
+impl Related<super::api_key_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiKeys.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ServiceAccountRequestDto {
+    #[validate(length(min = 3, max = 50, message = "Name must be between 3 and 50 characters long"))]
+    pub name: String,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters long"))]
+    #[serde(default)]
+    pub description: String,
+}
@@ -1,7 +1,6 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
-use crate::models::user_model;
 use crate::utill::validator::custom_text_check;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
@@ -10,7 +9,9 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: Uuid,
     pub grade: String,
-    pub class_teacher: String,
+    pub class_teacher_id: Option<Uuid>, // replaces the free-text class_teacher column
+    pub class_teacher: Option<String>, // legacy free-text name, cleared once the student is linked to a teacher
+    pub section_id: Option<Uuid>,
     pub user_id: Uuid,
 }
 
@@ -22,6 +23,22 @@ pub enum Relation {
         to = "super::user_model::Column::Id"
     )]
     User,
+    #[sea_orm(
+        belongs_to = "super::teacher_model::Entity",
+        from = "Column::ClassTeacherId",
+        to = "super::teacher_model::Column::Id",
+        on_delete = "SetNull"
+    )]
+    ClassTeacher,
+    #[sea_orm(
+        belongs_to = "super::section_model::Entity",
+        from = "Column::SectionId",
+        to = "super::section_model::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Section,
+    #[sea_orm(has_many = "super::enrollment_model::Entity")]
+    Enrollments,
 }
 
 impl Related<super::user_model::Entity> for Entity {
@@ -29,6 +46,24 @@ impl Related<super::user_model::Entity> for Entity {
         Relation::User.def()
     }
 }
+
+impl Related<super::teacher_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClassTeacher.def()
+    }
+}
+
+impl Related<super::section_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Section.def()
+    }
+}
+
+impl Related<super::enrollment_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Enrollments.def()
+    }
+}
 impl ActiveModelBehavior for ActiveModel {}
 
 
@@ -38,8 +73,8 @@ impl ActiveModelBehavior for ActiveModel {}
 pub struct StudentRequestDto {
     #[validate(length(min = 3, message = "Name must be at least 3 characters long"))]
     pub grade: String,
-    #[validate(length(min = 3, message = "Name must be at least 3 characters long"))]
-    pub class_teacher: String,
+    pub class_teacher_id: Option<Uuid>,
+    pub section_id: Option<Uuid>,
     #[validate(length(min = 5, message = "Name must be at least 5 characters long"), email(
         message = "Invalid email format"
     ))]
@@ -68,6 +103,8 @@ pub struct PaginateStudentResponseDto {
 pub struct StudentResponseDto {
     pub id: Uuid,
     pub grade: String,
-    pub class_teacher: String,
+    pub class_teacher_id: Option<Uuid>,
+    pub legacy_class_teacher: Option<String>, // an old class teacher name no teacher was linked for yet
+    pub section_id: Option<Uuid>,
     pub user_id: Uuid,
 }
@@ -0,0 +1,87 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// where a student was in a term, the students row only holds the active term
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "student_terms")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub student_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub term_id: Uuid,
+    pub grade: String,
+    pub class_teacher_id: Option<Uuid>,
+    pub section_id: Option<Uuid>,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::student_model::Entity",
+        from = "Column::StudentId",
+        to = "super::student_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Student,
+    #[sea_orm(
+        belongs_to = "super::term_model::Entity",
+        from = "Column::TermId",
+        to = "super::term_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Term,
+    #[sea_orm(
+        belongs_to = "super::teacher_model::Entity",
+        from = "Column::ClassTeacherId",
+        to = "super::teacher_model::Column::Id",
+        on_delete = "SetNull"
+    )]
+    ClassTeacher,
+    #[sea_orm(
+        belongs_to = "super::section_model::Entity",
+        from = "Column::SectionId",
+        to = "super::section_model::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Section,
+}
+
+impl Related<super::student_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Student.def()
+    }
+}
+
+impl Related<super::term_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Term.def()
+    }
+}
+
+impl Related<super::teacher_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClassTeacher.def()
+    }
+}
+
+impl Related<super::section_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Section.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentTermResponseDto {
+    pub term_id: Uuid,
+    pub term: String,
+    pub academic_year: String,
+    pub is_active: bool,
+    pub grade: String,
+    pub class_teacher_id: Option<Uuid>,
+    pub section_id: Option<Uuid>,
+    pub updated_at: DateTimeUtc,
+}
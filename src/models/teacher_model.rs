@@ -0,0 +1,94 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::utill::validator::custom_text_check;
+
+// a user who teaches, the name and email come from the account
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "teachers")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub user_id: Uuid,
+    pub department: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::user_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(has_many = "super::student_model::Entity")]
+    Students,
+    #[sea_orm(has_many = "super::section_model::Entity")]
+    HomeroomSections,
+}
+
+impl Related<super::user_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::student_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Students.def()
+    }
+}
+
+impl Related<super::section_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HomeroomSections.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TeacherRequestDto {
+    #[validate(length(min = 5, message = "Email must be at least 5 characters long"), email(
+        message = "Invalid email format"
+    ))]
+    pub user_email: String,
+    #[validate(length(min = 2, max = 100, message = "Department must be between 2 and 100 characters long"))]
+    pub department: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TeacherQueryOptions {
+    #[validate(custom = "custom_text_check")]
+    pub search_text: Option<String>,
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: u64,
+    #[validate(range(min = 1, max = 100, message = "Size must be between 1 and 100"))]
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaginateTeacherResponseDto {
+    pub count: u64,
+    pub list: Vec<TeacherResponseDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TeacherResponseDto {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub department: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LegacyClassTeachersResponseDto {
+    pub linked: u64,
+    pub unlinked: u64, // still to be assigned by hand, see legacy_class_teacher on the student
+}
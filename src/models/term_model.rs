@@ -0,0 +1,79 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// a part of an academic year, exactly one term is active once the first one exists
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "terms")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub academic_year_id: Uuid,
+    pub name: String,
+    pub start_date: Date,
+    pub end_date: Date,
+    pub is_active: bool, // a partial unique index keeps it to one row
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::academic_year_model::Entity",
+        from = "Column::AcademicYearId",
+        to = "super::academic_year_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    AcademicYear,
+    #[sea_orm(has_many = "super::student_term_model::Entity")]
+    StudentTerms,
+    #[sea_orm(has_many = "super::enrollment_model::Entity")]
+    Enrollments,
+}
+
+impl Related<super::academic_year_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AcademicYear.def()
+    }
+}
+
+impl Related<super::student_term_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StudentTerms.def()
+    }
+}
+
+impl Related<super::enrollment_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Enrollments.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TermRequestDto {
+    pub academic_year_id: Uuid,
+    #[validate(length(min = 1, max = 50, message = "Name must be between 1 and 50 characters long"))]
+    pub name: String,
+    pub start_date: Date,
+    pub end_date: Date,
+}
+
+// picks a term for term-scoped reads, the active term when missing
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TermQueryOptions {
+    pub term_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TermResponseDto {
+    pub id: Uuid,
+    pub academic_year_id: Uuid,
+    pub name: String,
+    pub start_date: Date,
+    pub end_date: Date,
+    pub is_active: bool,
+    pub created_at: DateTimeUtc,
+}
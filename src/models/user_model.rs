@@ -2,6 +2,9 @@ use sea_orm::entity::prelude::*;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use crate::midleware::permission::Role;
+use crate::models::recovery_code_model::MfaChallengeResponseDto;
+use crate::models::refresh_token_model::TokenResponseDto;
 use crate::utill::validator::{custom_text_check};
 
 // Define the Model (immutable representation of a row in the database)
@@ -12,14 +15,88 @@ pub struct Model {
     pub id: Uuid,
     pub name: String,
     pub email: String,
-    pub role: String,
+    pub role: UserRole,
     pub password: String,
+    pub email_verified: bool,
+    pub failed_login_attempts: i32, // consecutive failures, reset by a successful login
+    pub locked_until: Option<DateTimeUtc>, // no login attempts are checked before this
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>, // set on enrollment, used once confirmed
+    pub totp_enabled: bool,
+    #[serde(skip_serializing)]
+    pub totp_last_step: Option<i64>, // time step of the last accepted code, codes can't be replayed
+    pub tokens_revoked_at: Option<DateTimeUtc>, // tokens issued before this are rejected
+}
+
+// the user_role database enum, the built-in roles a user can hold
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "user_role")]
+pub enum UserRole {
+    #[sea_orm(string_value = "Admin")]
+    Admin,
+    #[sea_orm(string_value = "User")]
+    User,
+    #[sea_orm(string_value = "Student")]
+    Student,
+    #[sea_orm(string_value = "Teacher")]
+    Teacher,
+}
+
+impl UserRole {
+    // custom roles can't be stored in the users table
+    pub fn from_role(role: &Role) -> Option<UserRole> {
+        match role {
+            Role::Admin => Some(UserRole::Admin),
+            Role::User => Some(UserRole::User),
+            Role::Student => Some(UserRole::Student),
+            Role::Teacher => Some(UserRole::Teacher),
+            Role::Custom(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::Admin => "Admin",
+            UserRole::User => "User",
+            UserRole::Student => "Student",
+            UserRole::Teacher => "Teacher",
+        }
+    }
+}
+
+impl From<UserRole> for Role {
+    fn from(role: UserRole) -> Self {
+        match role {
+            UserRole::Admin => Role::Admin,
+            UserRole::User => Role::User,
+            UserRole::Student => Role::Student,
+            UserRole::Teacher => Role::Teacher,
+        }
+    }
 }
 
 #[derive(Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::student_model::Entity")]
     Students,
+    #[sea_orm(has_many = "super::refresh_token_model::Entity")]
+    RefreshTokens,
+    #[sea_orm(has_many = "super::password_reset_token_model::Entity")]
+    PasswordResetTokens,
+    #[sea_orm(has_many = "super::email_verification_token_model::Entity")]
+    EmailVerificationTokens,
+    #[sea_orm(has_many = "super::lockout_event_model::Entity")]
+    LockoutEvents,
+    #[sea_orm(has_many = "super::recovery_code_model::Entity")]
+    RecoveryCodes,
+    #[sea_orm(has_many = "super::session_model::Entity")]
+    Sessions,
+    #[sea_orm(has_many = "super::user_role_model::Entity")]
+    UserRoles,
+    #[sea_orm(has_many = "super::course_model::Entity")]
+    Courses,
+    #[sea_orm(has_one = "super::teacher_model::Entity")]
+    Teacher,
 }
 
 impl Related<super::student_model::Entity> for Entity {
@@ -27,6 +104,54 @@ impl Related<super::student_model::Entity> for Entity {
         Relation::Students.def()
     }
 }
+impl Related<super::refresh_token_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RefreshTokens.def()
+    }
+}
+impl Related<super::password_reset_token_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordResetTokens.def()
+    }
+}
+impl Related<super::email_verification_token_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EmailVerificationTokens.def()
+    }
+}
+impl Related<super::lockout_event_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LockoutEvents.def()
+    }
+}
+impl Related<super::recovery_code_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCodes.def()
+    }
+}
+impl Related<super::session_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sessions.def()
+    }
+}
+
+impl Related<super::user_role_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserRoles.def()
+    }
+}
+
+impl Related<super::course_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Courses.def()
+    }
+}
+
+impl Related<super::teacher_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Teacher.def()
+    }
+}
 impl ActiveModelBehavior for ActiveModel {}
 
 
@@ -41,6 +166,14 @@ pub struct LoginRequestDto {
     pub password: String,
 }
 
+// tokens, or a challenge when the account has two-factor authentication enabled
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum LoginResponseDto {
+    Tokens(TokenResponseDto),
+    MfaRequired(MfaChallengeResponseDto),
+}
+
 #[derive(Serialize, Deserialize, Debug, Validate)]
 pub struct UserRequestDto {
     #[validate(length(min = 3, message = "Name must be at least 3 characters long"))]
@@ -51,6 +184,45 @@ pub struct UserRequestDto {
     pub password: String,
 }
 
+// changes to the caller's own profile, fields left out stay as they are
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct UpdateProfileRequestDto {
+    #[validate(length(min = 3, message = "Name must be at least 3 characters long"))]
+    pub name: Option<String>,
+    #[validate(email(message = "Invalid email format"))]
+    pub email: Option<String>,
+    pub current_password: Option<String>, // required to change the email
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct ChangePasswordRequestDto {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+    #[validate(length(min = 4, message = "Password must be at least 4 characters long"))]
+    pub new_password: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct ImpersonateRequestDto {
+    #[validate(length(min = 5, max = 255, message = "Reason must be between 5 and 255 characters long"))]
+    pub reason: String, // written to the log with the start of the impersonation
+    pub read_only: Option<bool>, // default true
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImpersonationResponseDto {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub impersonated: String,
+    pub read_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct ChangeRoleRequestDto {
+    pub role: UserRole,
+}
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UserQueryOptions {
     #[validate(custom = "custom_text_check")]
@@ -73,7 +245,8 @@ pub struct UserResponseDto {
     pub id: Uuid,
     pub name: String,
     pub email: String,
-    pub role: String,
+    pub role: UserRole,
+    pub email_verified: bool,
 }
 
 
@@ -0,0 +1,60 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// roles a user holds in addition to the primary role in users.role
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_roles")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub role_name: String,
+    pub granted_at: DateTimeUtc,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::user_model::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::role_model::Entity",
+        from = "Column::RoleName",
+        to = "super::role_model::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Role,
+}
+
+impl Related<super::user_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::role_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Role.def()
+    }
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+
+//**********  dto **************
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct AddUserRoleRequestDto {
+    #[validate(length(min = 1, message = "Role is required"))]
+    pub role: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UserRolesResponseDto {
+    pub user_id: Uuid,
+    pub primary_role: String,
+    pub roles: Vec<String>,
+}
@@ -0,0 +1,30 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+use crate::exceptions::errors::SystemError;
+use crate::models::AcademicYear;
+use crate::models::academic_year_model::{ActiveModel, Column, Model};
+
+pub async fn create_academic_year_repo(db: &DatabaseConnection, academic_year: ActiveModel) -> Result<Model, SystemError> {
+    academic_year.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn update_academic_year_repo(db: &DatabaseConnection, academic_year: ActiveModel) -> Result<Model, SystemError> {
+    academic_year.update(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_academic_year_repo(db: &DatabaseConnection, academic_year: Model) -> Result<DeleteResult, SystemError> {
+    academic_year.delete(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_academic_year_by_name(db: &DatabaseConnection, name: &str) -> Result<Option<Model>, SystemError> {
+    AcademicYear::find()
+        .filter(Column::Name.eq(name))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_academic_years_repo(db: &DatabaseConnection) -> Result<Vec<Model>, SystemError> {
+    AcademicYear::find()
+        .order_by_desc(Column::StartDate)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
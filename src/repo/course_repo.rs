@@ -0,0 +1,61 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select};
+use uuid::Uuid;
+use sea_orm::sea_query::{Expr, Func};
+use crate::exceptions::errors::SystemError;
+use crate::models::Course;
+use crate::models::course_model::{ActiveModel, Column, CourseStatus, Model};
+
+pub async fn create_course_repo(db: &DatabaseConnection, course: ActiveModel) -> Result<Model, SystemError> {
+    course.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn update_course_repo(db: &DatabaseConnection, course: ActiveModel) -> Result<Model, SystemError> {
+    course.update(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_course_repo(db: &DatabaseConnection, course: Model) -> Result<DeleteResult, SystemError> {
+    course.delete(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_course_by_code(db: &DatabaseConnection, code: &str) -> Result<Option<Model>, SystemError> {
+    Course::find()
+        .filter(Column::Code.eq(code))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_courses_repo(db: &DatabaseConnection, search_text: &str, status: Option<CourseStatus>, page: u64, size: u64) -> Result<Vec<Model>, SystemError> {
+    let paginator = search_courses(search_text, status)
+        .order_by_asc(Column::Code)
+        .paginate(db, size);
+
+    paginator.fetch_page(page - 1).await.map_err(SystemError::DbError)
+}
+
+pub async fn all_courses_count_repo(db: &DatabaseConnection, search_text: &str, status: Option<CourseStatus>) -> Result<u64, SystemError> {
+    search_courses(search_text, status)
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// code or title containing the text, case insensitive
+fn search_courses(search_text: &str, status: Option<CourseStatus>) -> Select<Course> {
+    let pattern = format!("%{}%", search_text.to_lowercase());
+    let mut select = Course::find().filter(
+        Condition::any()
+            .add(Expr::expr(Func::lower(Expr::col(Column::Code))).like(&pattern))
+            .add(Expr::expr(Func::lower(Expr::col(Column::Title))).like(&pattern)),
+    );
+    if let Some(status) = status {
+        select = select.filter(Column::Status.eq(status));
+    }
+    select
+}
+
+// locks the course row until the transaction ends, seats of the course are counted and handed out one at a time
+pub async fn lock_course_repo<C: ConnectionTrait>(db: &C, id: Uuid) -> Result<Option<Model>, SystemError> {
+    Course::find_by_id(id)
+        .lock_exclusive()
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
@@ -0,0 +1,48 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::EmailVerificationToken;
+use crate::models::email_verification_token_model::{ActiveModel, Column, Model};
+
+pub async fn create_email_verification_token_repo(db: &DatabaseConnection, token: ActiveModel) -> Result<Model, SystemError> {
+    token.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_email_verification_token_by_hash(db: &DatabaseConnection, token_hash: &str) -> Result<Option<Model>, SystemError> {
+    EmailVerificationToken::find()
+        .filter(Column::TokenHash.eq(token_hash))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn latest_email_verification_token_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<Model>, SystemError> {
+    EmailVerificationToken::find()
+        .filter(Column::UserId.eq(user_id))
+        .order_by_desc(Column::CreatedAt)
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// only succeeds for an unused token, returns 0 when another request used it first
+pub async fn mark_email_verification_token_used_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    let result = EmailVerificationToken::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::UsedAt.is_null())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
+
+// a new verification mail replaces the links sent before, they may point at an old address
+pub async fn invalidate_user_email_verification_tokens_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<u64, SystemError> {
+    let result = EmailVerificationToken::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::UsedAt.is_null())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,72 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{course_model, student_model, Course, Enrollment, Student};
+use crate::models::enrollment_model::{ActiveModel, Column, EnrollmentStatus, Model};
+
+pub async fn create_enrollment_repo<C: ConnectionTrait>(db: &C, enrollment: ActiveModel) -> Result<Model, SystemError> {
+    enrollment.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn update_enrollment_repo<C: ConnectionTrait>(db: &C, enrollment: ActiveModel) -> Result<Model, SystemError> {
+    enrollment.update(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_enrollment<C: ConnectionTrait>(db: &C, course_id: Uuid, student_id: Uuid, term_id: Uuid) -> Result<Option<Model>, SystemError> {
+    Enrollment::find_by_id((course_id, student_id, term_id))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn course_enrollments_count_repo<C: ConnectionTrait>(db: &C, course_id: Uuid, term_id: Uuid, status: EnrollmentStatus) -> Result<u64, SystemError> {
+    Enrollment::find()
+        .filter(Column::CourseId.eq(course_id))
+        .filter(Column::TermId.eq(term_id))
+        .filter(Column::Status.eq(status))
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// the next student to get a seat
+pub async fn first_waitlisted_repo<C: ConnectionTrait>(db: &C, course_id: Uuid, term_id: Uuid) -> Result<Option<Model>, SystemError> {
+    Enrollment::find()
+        .filter(Column::CourseId.eq(course_id))
+        .filter(Column::TermId.eq(term_id))
+        .filter(Column::Status.eq(EnrollmentStatus::Waitlisted))
+        .order_by_asc(Column::StatusChangedAt)
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// waitlisted students ahead of this one
+pub async fn waitlisted_before_count_repo(db: &DatabaseConnection, enrollment: &Model) -> Result<u64, SystemError> {
+    Enrollment::find()
+        .filter(Column::CourseId.eq(enrollment.course_id))
+        .filter(Column::TermId.eq(enrollment.term_id))
+        .filter(Column::Status.eq(EnrollmentStatus::Waitlisted))
+        .filter(Column::StatusChangedAt.lt(enrollment.status_changed_at))
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// every enrollment of the course except dropped ones, waitlisted in promotion order
+pub async fn course_roster_repo(db: &DatabaseConnection, course_id: Uuid, term_id: Uuid) -> Result<Vec<(Model, Option<student_model::Model>)>, SystemError> {
+    Enrollment::find()
+        .filter(Column::CourseId.eq(course_id))
+        .filter(Column::TermId.eq(term_id))
+        .filter(Column::Status.ne(EnrollmentStatus::Dropped))
+        .order_by_asc(Column::StatusChangedAt)
+        .find_also_related(Student)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn student_enrollments_repo(db: &DatabaseConnection, student_id: Uuid, term_id: Uuid) -> Result<Vec<(Model, Option<course_model::Model>)>, SystemError> {
+    Enrollment::find()
+        .filter(Column::StudentId.eq(student_id))
+        .filter(Column::TermId.eq(term_id))
+        .order_by_desc(Column::StatusChangedAt)
+        .find_also_related(Course)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
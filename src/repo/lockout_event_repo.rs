@@ -0,0 +1,23 @@
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryOrder};
+use crate::exceptions::errors::SystemError;
+use crate::models::LockoutEvent;
+use crate::models::lockout_event_model::{ActiveModel, Column, Model};
+
+pub async fn create_lockout_event_repo(db: &DatabaseConnection, event: ActiveModel) -> Result<Model, SystemError> {
+    event.insert(db).await.map_err(SystemError::DbError)
+}
+
+// newest first
+pub async fn all_lockout_events_repo(db: &DatabaseConnection, page: u64, size: u64) -> Result<Vec<Model>, SystemError> {
+    let paginator = LockoutEvent::find()
+        .order_by_desc(Column::CreatedAt)
+        .paginate(db, size);
+
+    paginator.fetch_page(page - 1).await.map_err(SystemError::DbError)
+}
+
+pub async fn all_lockout_events_count_repo(db: &DatabaseConnection) -> Result<u64, SystemError> {
+    LockoutEvent::find()
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
@@ -1,2 +1,20 @@
 pub mod user_repo;
 pub mod student_repo;
+pub mod refresh_token_repo;
+pub mod revoked_token_repo;
+pub mod role_repo;
+pub mod password_reset_token_repo;
+pub mod email_verification_token_repo;
+pub mod lockout_event_repo;
+pub mod recovery_code_repo;
+pub mod service_account_repo;
+pub mod oidc_login_state_repo;
+pub mod session_repo;
+pub mod user_role_repo;
+pub mod course_repo;
+pub mod enrollment_repo;
+pub mod teacher_repo;
+pub mod section_repo;
+pub mod academic_year_repo;
+pub mod term_repo;
+pub mod student_term_repo;
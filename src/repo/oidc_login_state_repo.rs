@@ -0,0 +1,38 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::OidcLoginState;
+use crate::models::oidc_login_state_model::{ActiveModel, Column, Model};
+
+pub async fn create_oidc_login_state_repo(db: &DatabaseConnection, state: ActiveModel) -> Result<Model, SystemError> {
+    state.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_oidc_login_state_by_hash(db: &DatabaseConnection, state_hash: &str) -> Result<Option<Model>, SystemError> {
+    OidcLoginState::find()
+        .filter(Column::StateHash.eq(state_hash))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// only succeeds for an unused state, returns 0 when another request used it first
+pub async fn mark_oidc_login_state_used_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    let result = OidcLoginState::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::UsedAt.is_null())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
+
+// abandoned sign-ins are never called back, drop them once they can't be used anymore
+pub async fn delete_expired_oidc_login_states_repo(db: &DatabaseConnection) -> Result<u64, SystemError> {
+    let result = OidcLoginState::delete_many()
+        .filter(Column::ExpiresAt.lt(Utc::now()))
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,40 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::PasswordResetToken;
+use crate::models::password_reset_token_model::{ActiveModel, Column, Model};
+
+pub async fn create_password_reset_token_repo(db: &DatabaseConnection, token: ActiveModel) -> Result<Model, SystemError> {
+    token.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_password_reset_token_by_hash(db: &DatabaseConnection, token_hash: &str) -> Result<Option<Model>, SystemError> {
+    PasswordResetToken::find()
+        .filter(Column::TokenHash.eq(token_hash))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// only succeeds for an unused token, returns 0 when another request used it first
+pub async fn mark_password_reset_token_used_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    let result = PasswordResetToken::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::UsedAt.is_null())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
+
+// a new reset request replaces the links sent before
+pub async fn invalidate_user_password_reset_tokens_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<u64, SystemError> {
+    let result = PasswordResetToken::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::UsedAt.is_null())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
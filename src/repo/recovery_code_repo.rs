@@ -0,0 +1,41 @@
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, DeleteResult, EntityTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::RecoveryCode;
+use crate::models::recovery_code_model::{ActiveModel, Column, Model};
+
+pub async fn create_recovery_codes_repo(db: &DatabaseConnection, codes: Vec<ActiveModel>) -> Result<(), SystemError> {
+    RecoveryCode::insert_many(codes)
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(())
+}
+
+pub async fn find_unused_recovery_code(db: &DatabaseConnection, user_id: Uuid, code_hash: &str) -> Result<Option<Model>, SystemError> {
+    RecoveryCode::find()
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::CodeHash.eq(code_hash))
+        .filter(Column::UsedAt.is_null())
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// only succeeds for an unused code, returns 0 when another request used it first
+pub async fn mark_recovery_code_used_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    let result = RecoveryCode::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::UsedAt.is_null())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
+
+pub async fn delete_user_recovery_codes_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<DeleteResult, SystemError> {
+    RecoveryCode::delete_many()
+        .filter(Column::UserId.eq(user_id))
+        .exec(db)
+        .await.map_err(SystemError::DbError)
+}
@@ -0,0 +1,53 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::RefreshToken;
+use crate::models::refresh_token_model::{ActiveModel, Column, Model};
+
+pub async fn create_refresh_token_repo(db: &DatabaseConnection, token: ActiveModel) -> Result<Model, SystemError> {
+    token.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_refresh_token_by_hash(db: &DatabaseConnection, token_hash: &String) -> Result<Option<Model>, SystemError> {
+    RefreshToken::find()
+        .filter(Column::TokenHash.eq(token_hash))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// mark a token as used, returns 0 when it was already used or revoked
+pub async fn mark_refresh_token_used_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    RefreshToken::update_many()
+        .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::UsedAt.is_null())
+        .filter(Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
+
+pub async fn revoke_token_family_repo(db: &DatabaseConnection, family_id: Uuid) -> Result<u64, SystemError> {
+    RefreshToken::update_many()
+        .col_expr(Column::RevokedAt, Expr::value(Utc::now()))
+        .filter(Column::FamilyId.eq(family_id))
+        .filter(Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
+
+pub async fn revoke_user_refresh_tokens_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<u64, SystemError> {
+    RefreshToken::update_many()
+        .col_expr(Column::RevokedAt, Expr::value(Utc::now()))
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
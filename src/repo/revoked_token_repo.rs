@@ -0,0 +1,25 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use crate::exceptions::errors::SystemError;
+use crate::models::RevokedToken;
+use crate::models::revoked_token_model::{ActiveModel, Column, Model};
+
+pub async fn create_revoked_token_repo(db: &DatabaseConnection, token: ActiveModel) -> Result<Model, SystemError> {
+    token.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_revoked_token_by_jti(db: &DatabaseConnection, jti: &str) -> Result<Option<Model>, SystemError> {
+    RevokedToken::find_by_id(jti.to_string())
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// expired tokens are rejected anyway, so their denylist entries can go
+pub async fn delete_expired_revoked_tokens_repo(db: &DatabaseConnection) -> Result<u64, SystemError> {
+    RevokedToken::delete_many()
+        .filter(Column::ExpiresAt.lt(Utc::now()))
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
@@ -0,0 +1,48 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+use crate::exceptions::errors::SystemError;
+use crate::models::{permission_model, role_model, role_permission_model, PermissionEntity, RoleEntity, RolePermission};
+
+pub async fn create_role_repo(db: &DatabaseConnection, role: role_model::ActiveModel) -> Result<role_model::Model, SystemError> {
+    role.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_role_by_name(db: &DatabaseConnection, name: &str) -> Result<Option<role_model::Model>, SystemError> {
+    RoleEntity::find_by_id(name.to_string())
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_roles_repo(db: &DatabaseConnection) -> Result<Vec<role_model::Model>, SystemError> {
+    RoleEntity::find()
+        .order_by_asc(role_model::Column::Name)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_permission_by_name(db: &DatabaseConnection, name: &str) -> Result<Option<permission_model::Model>, SystemError> {
+    PermissionEntity::find_by_id(name.to_string())
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_role_permissions_repo(db: &DatabaseConnection) -> Result<Vec<role_permission_model::Model>, SystemError> {
+    RolePermission::find()
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_role_permission(db: &DatabaseConnection, role_name: &str, permission_name: &str) -> Result<Option<role_permission_model::Model>, SystemError> {
+    RolePermission::find()
+        .filter(role_permission_model::Column::RoleName.eq(role_name))
+        .filter(role_permission_model::Column::PermissionName.eq(permission_name))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn create_role_permission_repo(db: &DatabaseConnection, role_permission: role_permission_model::ActiveModel) -> Result<role_permission_model::Model, SystemError> {
+    role_permission.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_role_permission_repo(db: &DatabaseConnection, role_permission: role_permission_model::Model) -> Result<DeleteResult, SystemError> {
+    role_permission.delete(db).await.map_err(SystemError::DbError)
+}
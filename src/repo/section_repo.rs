@@ -0,0 +1,90 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, Select, Set};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{academic_year_model, student_model, AcademicYear, Section, Student};
+use crate::models::section_model::{ActiveModel, Column, Model};
+
+pub async fn create_section_repo(db: &DatabaseConnection, section: ActiveModel) -> Result<Model, SystemError> {
+    section.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn update_section_repo(db: &DatabaseConnection, section: ActiveModel) -> Result<Model, SystemError> {
+    section.update(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_section_repo(db: &DatabaseConnection, section: Model) -> Result<DeleteResult, SystemError> {
+    section.delete(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_section_by_name(db: &DatabaseConnection, grade_level: i32, section_letter: &str, academic_year_id: Uuid) -> Result<Option<Model>, SystemError> {
+    Section::find()
+        .filter(Column::GradeLevel.eq(grade_level))
+        .filter(Column::SectionLetter.eq(section_letter))
+        .filter(Column::AcademicYearId.eq(academic_year_id))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_sections_repo(db: &DatabaseConnection, academic_year_id: Option<Uuid>, grade_level: Option<i32>, page: u64, size: u64) -> Result<Vec<(Model, Option<academic_year_model::Model>)>, SystemError> {
+    let paginator = filter_sections(academic_year_id, grade_level)
+        .find_also_related(AcademicYear)
+        .order_by_desc(academic_year_model::Column::StartDate)
+        .order_by_asc(Column::GradeLevel)
+        .order_by_asc(Column::SectionLetter)
+        .paginate(db, size);
+
+    paginator.fetch_page(page - 1).await.map_err(SystemError::DbError)
+}
+
+pub async fn all_sections_count_repo(db: &DatabaseConnection, academic_year_id: Option<Uuid>, grade_level: Option<i32>) -> Result<u64, SystemError> {
+    filter_sections(academic_year_id, grade_level)
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn academic_year_sections_count_repo(db: &DatabaseConnection, academic_year_id: Uuid) -> Result<u64, SystemError> {
+    Section::find()
+        .filter(Column::AcademicYearId.eq(academic_year_id))
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_students_by_ids<C: ConnectionTrait>(db: &C, ids: Vec<Uuid>) -> Result<Vec<student_model::Model>, SystemError> {
+    Student::find()
+        .filter(student_model::Column::Id.is_in(ids))
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// put the students in the section, a homeroom teacher becomes their class teacher
+pub async fn move_students_repo<C: ConnectionTrait>(db: &C, ids: Vec<Uuid>, section: &Model) -> Result<u64, SystemError> {
+    let mut update = Student::update_many()
+        .col_expr(student_model::Column::SectionId, Expr::value(section.id));
+    if let Some(teacher_id) = section.homeroom_teacher_id {
+        update = update.col_expr(student_model::Column::ClassTeacherId, Expr::value(teacher_id));
+    }
+    update
+        .filter(student_model::Column::Id.is_in(ids))
+        .exec(db)
+        .await
+        .map(|result| result.rows_affected)
+        .map_err(SystemError::DbError)
+}
+
+pub async fn remove_student_from_section_repo<C: ConnectionTrait>(db: &C, student: student_model::Model) -> Result<student_model::Model, SystemError> {
+    let mut active_student: student_model::ActiveModel = student.into();
+    active_student.section_id = Set(None);
+    active_student.update(db).await.map_err(SystemError::DbError)
+}
+
+fn filter_sections(academic_year_id: Option<Uuid>, grade_level: Option<i32>) -> Select<Section> {
+    let mut select = Section::find();
+    if let Some(academic_year_id) = academic_year_id {
+        select = select.filter(Column::AcademicYearId.eq(academic_year_id));
+    }
+    if let Some(grade_level) = grade_level {
+        select = select.filter(Column::GradeLevel.eq(grade_level));
+    }
+    select
+}
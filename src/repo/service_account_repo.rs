@@ -0,0 +1,87 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{api_key_model, api_key_permission_model, service_account_model, ApiKey, ApiKeyPermission, ServiceAccount};
+
+pub async fn create_service_account_repo(db: &DatabaseConnection, account: service_account_model::ActiveModel) -> Result<service_account_model::Model, SystemError> {
+    account.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_service_account_repo(db: &DatabaseConnection, account: service_account_model::Model) -> Result<DeleteResult, SystemError> {
+    account.delete(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_service_account_by_id(db: &DatabaseConnection, id: Uuid) -> Result<Option<service_account_model::Model>, SystemError> {
+    ServiceAccount::find_by_id(id)
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_service_account_by_name(db: &DatabaseConnection, name: &str) -> Result<Option<service_account_model::Model>, SystemError> {
+    ServiceAccount::find()
+        .filter(service_account_model::Column::Name.eq(name))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_service_accounts_repo(db: &DatabaseConnection) -> Result<Vec<service_account_model::Model>, SystemError> {
+    ServiceAccount::find()
+        .order_by_asc(service_account_model::Column::Name)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn create_api_key_repo(db: &DatabaseConnection, key: api_key_model::ActiveModel) -> Result<api_key_model::Model, SystemError> {
+    key.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn update_api_key_repo(db: &DatabaseConnection, key: api_key_model::ActiveModel) -> Result<api_key_model::Model, SystemError> {
+    key.update(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_api_key_by_id(db: &DatabaseConnection, id: Uuid) -> Result<Option<api_key_model::Model>, SystemError> {
+    ApiKey::find_by_id(id)
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_api_key_by_hash(db: &DatabaseConnection, key_hash: &str) -> Result<Option<api_key_model::Model>, SystemError> {
+    ApiKey::find()
+        .filter(api_key_model::Column::KeyHash.eq(key_hash))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn service_account_api_keys_repo(db: &DatabaseConnection, service_account_id: Uuid) -> Result<Vec<api_key_model::Model>, SystemError> {
+    ApiKey::find()
+        .filter(api_key_model::Column::ServiceAccountId.eq(service_account_id))
+        .order_by_desc(api_key_model::Column::CreatedAt)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn touch_api_key_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    let result = ApiKey::update_many()
+        .col_expr(api_key_model::Column::LastUsedAt, Expr::value(Utc::now()))
+        .filter(api_key_model::Column::Id.eq(id))
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected)
+}
+
+pub async fn create_api_key_permissions_repo(db: &DatabaseConnection, permissions: Vec<api_key_permission_model::ActiveModel>) -> Result<(), SystemError> {
+    ApiKeyPermission::insert_many(permissions)
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(())
+}
+
+pub async fn api_key_permissions_repo(db: &DatabaseConnection, api_key_id: Uuid) -> Result<Vec<String>, SystemError> {
+    let permissions = ApiKeyPermission::find()
+        .filter(api_key_permission_model::Column::ApiKeyId.eq(api_key_id))
+        .all(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(permissions.into_iter().map(|permission| permission.permission_name).collect())
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::Session;
+use crate::models::session_model::{ActiveModel, Column, Model};
+
+pub async fn create_session_repo(db: &DatabaseConnection, session: ActiveModel) -> Result<Model, SystemError> {
+    session.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_session_by_id(db: &DatabaseConnection, id: Uuid) -> Result<Option<Model>, SystemError> {
+    Session::find_by_id(id).one(db).await.map_err(SystemError::DbError)
+}
+
+// sessions that can still be refreshed, most recently used first
+pub async fn user_active_sessions_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<Model>, SystemError> {
+    Session::find()
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::RevokedAt.is_null())
+        .filter(Column::ExpiresAt.gt(Utc::now()))
+        .order_by_desc(Column::LastUsedAt)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// called on every request, only writes when the last recorded use is a minute old
+pub async fn touch_session_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    let now = Utc::now();
+    Session::update_many()
+        .col_expr(Column::LastUsedAt, Expr::value(now))
+        .filter(Column::Id.eq(id))
+        .filter(Column::LastUsedAt.lt(now - Duration::minutes(1)))
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
+
+pub async fn extend_session_repo(db: &DatabaseConnection, id: Uuid, expires_at: DateTime<Utc>) -> Result<u64, SystemError> {
+    Session::update_many()
+        .col_expr(Column::LastUsedAt, Expr::value(Utc::now()))
+        .col_expr(Column::ExpiresAt, Expr::value(expires_at))
+        .filter(Column::Id.eq(id))
+        .filter(Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
+
+pub async fn revoke_session_repo(db: &DatabaseConnection, id: Uuid) -> Result<u64, SystemError> {
+    Session::update_many()
+        .col_expr(Column::RevokedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.eq(id))
+        .filter(Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
+
+pub async fn revoke_user_sessions_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<u64, SystemError> {
+    Session::update_many()
+        .col_expr(Column::RevokedAt, Expr::value(Utc::now()))
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .map(|res| res.rows_affected)
+        .map_err(SystemError::DbError)
+}
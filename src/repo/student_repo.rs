@@ -3,17 +3,18 @@ use crate::exceptions::errors::SystemError;
 use crate::models::student_model::{Entity, Model, Column, ActiveModel};
 use crate::models::{user_model, Student};
 use sea_orm::QueryFilter;
+use uuid::Uuid;
 
 pub async fn create_student_repo(db: &DatabaseConnection, student: ActiveModel) -> Result<Model, SystemError> {
-    student.insert(db).await.map_err(|e| SystemError::DbError(e))
+    student.insert(db).await.map_err(SystemError::DbError)
 }
 
 pub async fn update_student_repo(db: &DatabaseConnection, student: ActiveModel) -> Result<Model, SystemError> {
-    student.update(db).await.map_err(|e| SystemError::DbError(e))
+    student.update(db).await.map_err(SystemError::DbError)
 }
 
 pub async fn delete_student_repo(db: &DatabaseConnection, student: Model) -> Result<DeleteResult, SystemError> {
-    student.delete(db).await.map_err(|e| SystemError::DbError(e))
+    student.delete(db).await.map_err(SystemError::DbError)
 }
 
 pub async fn all_students_repo(db: &DatabaseConnection, search_text: &String, page: u64, size: u64) -> Result<Vec<Model>, SystemError> {
@@ -21,16 +22,23 @@ pub async fn all_students_repo(db: &DatabaseConnection, search_text: &String, pa
         .filter(Column::Grade.contains(search_text))
         .paginate(db, size);
 
-    paginator.fetch_page(page - 1).await.map_err(|e| SystemError::DbError(e))
+    paginator.fetch_page(page - 1).await.map_err(SystemError::DbError)
 }
 
 pub async fn all_students_count_repo(db: &DatabaseConnection, search_text: &String) -> Result<u64, SystemError> {
     Student::find()
         .filter(Column::Grade.contains(search_text))
         .count(db)
-        .await.map_err(|e| SystemError::DbError(e))
+        .await.map_err(SystemError::DbError)
 }
 
 pub async fn find_student_by_user(db: &DatabaseConnection, user: user_model::Model) -> Result<Option<Model>, SystemError> {
-    user.find_related(Entity).one(db).await.map_err(|e| SystemError::DbError(e))
-}
\ No newline at end of file
+    user.find_related(Entity).one(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_student_by_user_id(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<Model>, SystemError> {
+    Student::find()
+        .filter(Column::UserId.eq(user_id))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
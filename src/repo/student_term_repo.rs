@@ -0,0 +1,41 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use sea_orm::sea_query::OnConflict;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{student_model, term_model, Student, StudentTerm, Term};
+use crate::models::student_term_model::{ActiveModel, Column, Model};
+
+pub async fn find_student_term<C: ConnectionTrait>(db: &C, student_id: Uuid, term_id: Uuid) -> Result<Option<Model>, SystemError> {
+    StudentTerm::find_by_id((student_id, term_id))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn save_student_term_repo<C: ConnectionTrait>(db: &C, student_term: ActiveModel) -> Result<(), SystemError> {
+    StudentTerm::insert(student_term)
+        .on_conflict(OnConflict::columns([Column::StudentId, Column::TermId])
+            .update_columns([Column::Grade, Column::ClassTeacherId, Column::SectionId, Column::UpdatedAt])
+            .to_owned())
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(())
+}
+
+// a student's placements, newest term first
+pub async fn student_terms_repo(db: &DatabaseConnection, student_id: Uuid) -> Result<Vec<(Model, Option<term_model::Model>)>, SystemError> {
+    StudentTerm::find()
+        .filter(Column::StudentId.eq(student_id))
+        .find_also_related(Term)
+        .order_by_desc(term_model::Column::StartDate)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn section_student_terms_repo(db: &DatabaseConnection, section_id: Uuid, term_id: Uuid) -> Result<Vec<(Model, Option<student_model::Model>)>, SystemError> {
+    StudentTerm::find()
+        .filter(Column::SectionId.eq(section_id))
+        .filter(Column::TermId.eq(term_id))
+        .find_also_related(Student)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
@@ -0,0 +1,82 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{student_model, user_model, Student, Teacher, User};
+use crate::models::teacher_model::{ActiveModel, Column, Model};
+
+pub async fn create_teacher_repo(db: &DatabaseConnection, teacher: ActiveModel) -> Result<Model, SystemError> {
+    teacher.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_teacher_repo(db: &DatabaseConnection, teacher: Model) -> Result<DeleteResult, SystemError> {
+    teacher.delete(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_teacher_by_user_id(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<Model>, SystemError> {
+    Teacher::find()
+        .filter(Column::UserId.eq(user_id))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn all_teachers_repo(db: &DatabaseConnection, search_text: &String, page: u64, size: u64) -> Result<Vec<(Model, Option<user_model::Model>)>, SystemError> {
+    let paginator = Teacher::find()
+        .find_also_related(User)
+        .filter(user_model::Column::Name.contains(search_text))
+        .order_by_asc(user_model::Column::Name)
+        .paginate(db, size);
+
+    paginator.fetch_page(page - 1).await.map_err(SystemError::DbError)
+}
+
+pub async fn all_teachers_count_repo(db: &DatabaseConnection, search_text: &String) -> Result<u64, SystemError> {
+    Teacher::find()
+        .find_also_related(User)
+        .filter(user_model::Column::Name.contains(search_text))
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn teacher_students_repo(db: &DatabaseConnection, teacher_id: Uuid) -> Result<Vec<(student_model::Model, Option<user_model::Model>)>, SystemError> {
+    Student::find()
+        .filter(student_model::Column::ClassTeacherId.eq(teacher_id))
+        .find_also_related(User)
+        .order_by_asc(user_model::Column::Name)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+// link students whose free-text class_teacher names exactly one teacher, titles like "Mrs" and case are ignored,
+// a name of one word ("Mrs Smith") is matched on the teacher's last name, linked students lose the old name
+pub async fn link_legacy_class_teachers_repo<C: ConnectionTrait>(db: &C) -> Result<u64, SystemError> {
+    let mut linked = 0;
+    for name_part in ["name", "last_name"] {
+        let statement = format!(r"
+            WITH teacher_names AS (
+                SELECT t.id,
+                       regexp_replace(regexp_replace(lower(trim(u.name)), '\s+', ' ', 'g'), '^(mr|mrs|ms|miss|dr|prof)\.? ', '') AS name
+                FROM teachers t JOIN users u ON u.id = t.user_id
+            ), candidates AS (
+                SELECT id, name, regexp_replace(name, '^.* ', '') AS last_name FROM teacher_names
+            ), unique_names AS (
+                SELECT {name_part} AS name, min(id::text)::uuid AS id FROM candidates GROUP BY {name_part} HAVING count(*) = 1
+            )
+            UPDATE students s SET class_teacher_id = n.id, class_teacher = NULL
+            FROM unique_names n
+            WHERE s.class_teacher_id IS NULL
+              AND s.class_teacher IS NOT NULL
+              AND regexp_replace(regexp_replace(lower(trim(s.class_teacher)), '\s+', ' ', 'g'), '^(mr|mrs|ms|miss|dr|prof)\.? ', '') = n.name
+        ");
+        linked += db.execute_unprepared(&statement).await.map_err(SystemError::DbError)?.rows_affected();
+    }
+    Ok(linked)
+}
+
+// students still without a teacher that have a legacy name
+pub async fn unlinked_legacy_class_teachers_count_repo<C: ConnectionTrait>(db: &C) -> Result<u64, SystemError> {
+    Student::find()
+        .filter(student_model::Column::ClassTeacherId.is_null())
+        .filter(student_model::Column::ClassTeacher.is_not_null())
+        .count(db)
+        .await.map_err(SystemError::DbError)
+}
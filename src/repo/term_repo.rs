@@ -0,0 +1,84 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DeleteResult, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, Statement};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{academic_year_model, enrollment_model, student_term_model, AcademicYear, Enrollment, StudentTerm, Term};
+use crate::models::term_model::{ActiveModel, Column, Model};
+
+pub async fn create_term_repo<C: ConnectionTrait>(db: &C, term: ActiveModel) -> Result<Model, SystemError> {
+    term.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn update_term_repo<C: ConnectionTrait>(db: &C, term: ActiveModel) -> Result<Model, SystemError> {
+    term.update(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_term_repo(db: &DatabaseConnection, term: Model) -> Result<DeleteResult, SystemError> {
+    term.delete(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_active_term<C: ConnectionTrait>(db: &C) -> Result<Option<Model>, SystemError> {
+    Term::find()
+        .filter(Column::IsActive.eq(true))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_term_with_academic_year<C: ConnectionTrait>(db: &C, id: Uuid) -> Result<Option<(Model, Option<academic_year_model::Model>)>, SystemError> {
+    Term::find_by_id(id)
+        .find_also_related(AcademicYear)
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn academic_year_terms_repo(db: &DatabaseConnection, academic_year_id: Uuid) -> Result<Vec<Model>, SystemError> {
+    Term::find()
+        .filter(Column::AcademicYearId.eq(academic_year_id))
+        .order_by_asc(Column::StartDate)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn deactivate_terms_repo<C: ConnectionTrait>(db: &C) -> Result<(), SystemError> {
+    Term::update_many()
+        .col_expr(Column::IsActive, Expr::value(false))
+        .filter(Column::IsActive.eq(true))
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(())
+}
+
+// what would be lost with the term
+pub async fn term_history_count_repo(db: &DatabaseConnection, term_id: Uuid) -> Result<u64, SystemError> {
+    let enrollments = Enrollment::find()
+        .filter(enrollment_model::Column::TermId.eq(term_id))
+        .count(db)
+        .await.map_err(SystemError::DbError)?;
+    let placements = StudentTerm::find()
+        .filter(student_term_model::Column::TermId.eq(term_id))
+        .count(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(enrollments + placements)
+}
+
+// students without a placement in the term keep their current one,
+// except a section of another academic year
+pub async fn carry_over_placements_repo<C: ConnectionTrait>(db: &C, term_id: Uuid, academic_year_id: Uuid) -> Result<u64, SystemError> {
+    let statement = Statement::from_sql_and_values(DbBackend::Postgres, r"
+        INSERT INTO student_terms (student_id, term_id, grade, class_teacher_id, section_id, updated_at)
+        SELECT s.id, $1, s.grade, s.class_teacher_id, CASE WHEN sec.academic_year_id = $2 THEN s.section_id END, now()
+        FROM students s LEFT JOIN sections sec ON sec.id = s.section_id
+        ON CONFLICT (student_id, term_id) DO NOTHING
+    ", [term_id.into(), academic_year_id.into()]);
+    Ok(db.execute(statement).await.map_err(SystemError::DbError)?.rows_affected())
+}
+
+// the students rows follow the placements of the newly active term
+pub async fn apply_placements_repo<C: ConnectionTrait>(db: &C, term_id: Uuid) -> Result<u64, SystemError> {
+    let statement = Statement::from_sql_and_values(DbBackend::Postgres, r"
+        UPDATE students s SET grade = st.grade, class_teacher_id = st.class_teacher_id, section_id = st.section_id
+        FROM student_terms st
+        WHERE st.student_id = s.id AND st.term_id = $1
+    ", [term_id.into()]);
+    Ok(db.execute(statement).await.map_err(SystemError::DbError)?.rows_affected())
+}
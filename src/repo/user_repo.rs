@@ -1,19 +1,62 @@
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, PaginatorTrait};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, PaginatorTrait, QuerySelect};
 use crate::exceptions::errors::SystemError;
 use crate::models::User;
-use crate::models::user_model::{ActiveModel, Model, Column};
+use crate::models::user_model::{ActiveModel, Model, Column, UserRole};
 use sea_orm::QueryFilter;
+use sea_orm::sea_query::Expr;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
 pub async fn create_user_repo(db: &DatabaseConnection, user: ActiveModel) -> Result<Model, SystemError> {
-    user.insert(db).await.map_err(|e| SystemError::DbError(e))
+    user.insert(db).await.map_err(SystemError::DbError)
 }
 
-pub async fn update_user_repo(db: &DatabaseConnection, user: ActiveModel) -> Result<Model, SystemError> {
-    user.update(db).await.map_err(|e| SystemError::DbError(e))
+pub async fn update_user_repo<C: ConnectionTrait>(db: &C, user: ActiveModel) -> Result<Model, SystemError> {
+    user.update(db).await.map_err(SystemError::DbError)
 }
 
-pub async fn delete_user_repo(db: &DatabaseConnection, user: Model) -> Result<DeleteResult, SystemError> {
-    user.delete(db).await.map_err(|e| SystemError::DbError(e))
+// counts in the database so concurrent failures can't overwrite each other
+pub async fn increment_failed_logins_repo(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<Model>, SystemError> {
+    let users = User::update_many()
+        .col_expr(Column::FailedLoginAttempts, Expr::col(Column::FailedLoginAttempts).add(1))
+        .filter(Column::Id.eq(user_id))
+        .exec_with_returning(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(users.into_iter().next())
+}
+
+// never shortens a wait another failure already set
+pub async fn extend_locked_until_repo(db: &DatabaseConnection, user_id: Uuid, locked_until: DateTime<Utc>) -> Result<(), SystemError> {
+    User::update_many()
+        .col_expr(Column::LockedUntil, Expr::cust_with_values("GREATEST(locked_until, $1)", [locked_until]))
+        .filter(Column::Id.eq(user_id))
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(())
+}
+
+// only succeeds for a step newer than the last used one, false means the code was replayed
+pub async fn claim_totp_step_repo(db: &DatabaseConnection, user_id: Uuid, step: i64) -> Result<bool, SystemError> {
+    let result = User::update_many()
+        .col_expr(Column::TotpLastStep, Expr::value(step))
+        .filter(Column::Id.eq(user_id))
+        .filter(Column::TotpLastStep.is_null().or(Column::TotpLastStep.lt(step)))
+        .exec(db)
+        .await.map_err(SystemError::DbError)?;
+    Ok(result.rows_affected == 1)
+}
+
+pub async fn delete_user_repo<C: ConnectionTrait>(db: &C, user: Model) -> Result<DeleteResult, SystemError> {
+    user.delete(db).await.map_err(SystemError::DbError)
+}
+
+// locks the admin rows until the transaction ends, two demotions can't both count the other admin
+pub async fn lock_admins_repo<C: ConnectionTrait>(db: &C) -> Result<Vec<Model>, SystemError> {
+    User::find()
+        .filter(Column::Role.eq(UserRole::Admin))
+        .lock_exclusive()
+        .all(db)
+        .await.map_err(SystemError::DbError)
 }
 
 pub async fn all_users_repo(db: &DatabaseConnection, search_text: &String, page: u64, size: u64) -> Result<Vec<Model>, SystemError> {
@@ -21,19 +64,26 @@ pub async fn all_users_repo(db: &DatabaseConnection, search_text: &String, page:
         .filter(Column::Name.contains(search_text))
         .paginate(db, size);
 
-    paginator.fetch_page(page - 1).await.map_err(|e| SystemError::DbError(e))
+    paginator.fetch_page(page - 1).await.map_err(SystemError::DbError)
 }
 
 pub async fn all_users_count_repo(db: &DatabaseConnection, search_text: &String) -> Result<u64, SystemError> {
     User::find()
         .filter(Column::Name.contains(search_text))
         .count(db)
-        .await.map_err(|e| SystemError::DbError(e))
+        .await.map_err(SystemError::DbError)
 }
 
 pub async fn find_user_by_email(db: &DatabaseConnection, email: &String) -> Result<Option<Model>, SystemError> {
     User::find()
         .filter(Column::Email.eq(email))
         .one(db)
-        .await.map_err(|e| SystemError::DbError(e))
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn find_users_by_ids(db: &DatabaseConnection, ids: Vec<Uuid>) -> Result<Vec<Model>, SystemError> {
+    User::find()
+        .filter(Column::Id.is_in(ids))
+        .all(db)
+        .await.map_err(SystemError::DbError)
 }
@@ -0,0 +1,36 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::UserRoleEntity;
+use crate::models::user_role_model::{ActiveModel, Column, Model};
+
+pub async fn create_user_role_repo(db: &DatabaseConnection, user_role: ActiveModel) -> Result<Model, SystemError> {
+    user_role.insert(db).await.map_err(SystemError::DbError)
+}
+
+pub async fn find_user_role<C: ConnectionTrait>(db: &C, user_id: Uuid, role_name: &str) -> Result<Option<Model>, SystemError> {
+    UserRoleEntity::find_by_id((user_id, role_name.to_string()))
+        .one(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn user_roles_repo<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<Vec<Model>, SystemError> {
+    UserRoleEntity::find()
+        .filter(Column::UserId.eq(user_id))
+        .order_by_asc(Column::RoleName)
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
+
+pub async fn delete_user_role_repo<C: ConnectionTrait>(db: &C, user_role: Model) -> Result<DeleteResult, SystemError> {
+    user_role.delete(db).await.map_err(SystemError::DbError)
+}
+
+// locks the holders of the role until the transaction ends
+pub async fn lock_role_holders_repo<C: ConnectionTrait>(db: &C, role_name: &str) -> Result<Vec<Model>, SystemError> {
+    UserRoleEntity::find()
+        .filter(Column::RoleName.eq(role_name))
+        .lock_exclusive()
+        .all(db)
+        .await.map_err(SystemError::DbError)
+}
@@ -0,0 +1,124 @@
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{DatabaseConnection, DeleteResult, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::models::{academic_year_model, AcademicYear};
+use crate::models::academic_year_model::{AcademicYearRequestDto, AcademicYearResponseDto, Model};
+use crate::repo::academic_year_repo::{all_academic_years_repo, create_academic_year_repo, delete_academic_year_repo, find_academic_year_by_name, update_academic_year_repo};
+use crate::repo::section_repo::academic_year_sections_count_repo;
+use crate::repo::term_repo::academic_year_terms_repo;
+
+pub async fn create_academic_year_service(db: &DatabaseConnection, dto: AcademicYearRequestDto, actor: &AuthenticatedUser) -> Result<AcademicYearResponseDto, SystemError> {
+    check_dates(&dto)?;
+    if find_academic_year_by_name(db, &dto.name).await?.is_some() {
+        return Err(SystemError::DuplicateError(dto.name + " academic year"));
+    }
+
+    let new_academic_year = academic_year_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(dto.name),
+        start_date: Set(dto.start_date),
+        end_date: Set(dto.end_date),
+        created_at: Set(Utc::now()),
+    };
+    match create_academic_year_repo(db, new_academic_year).await {
+        Ok(academic_year) => {
+            info!("academic year successfully created by {}: {:?}", actor.email, academic_year);
+            Ok(create_response_dto(&academic_year))
+        }
+        Err(e) => {
+            error!("Failed to create academic year: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+// the new dates still have to hold every term of the year
+pub async fn update_academic_year_service(db: &DatabaseConnection, id: String, dto: AcademicYearRequestDto, actor: &AuthenticatedUser) -> Result<AcademicYearResponseDto, SystemError> {
+    let selected_academic_year = find_academic_year(db, &id).await?;
+    check_dates(&dto)?;
+    let existing = find_academic_year_by_name(db, &dto.name).await?;
+    if existing.is_some_and(|academic_year| academic_year.id != selected_academic_year.id) {
+        return Err(SystemError::DuplicateError(dto.name + " academic year"));
+    }
+    let outside = academic_year_terms_repo(db, selected_academic_year.id).await?
+        .into_iter()
+        .find(|term| term.start_date < dto.start_date || term.end_date > dto.end_date);
+    if let Some(term) = outside {
+        return Err(SystemError::ValidationError(format!("term {} ({} to {}) would fall outside the academic year", term.name, term.start_date, term.end_date)));
+    }
+
+    let mut active_academic_year: academic_year_model::ActiveModel = selected_academic_year.into();
+    active_academic_year.name = Set(dto.name);
+    active_academic_year.start_date = Set(dto.start_date);
+    active_academic_year.end_date = Set(dto.end_date);
+
+    match update_academic_year_repo(db, active_academic_year).await {
+        Ok(academic_year) => {
+            info!("academic year successfully updated by {}: {:?}", actor.email, academic_year);
+            Ok(create_response_dto(&academic_year))
+        }
+        Err(e) => {
+            error!("Failed to update academic year: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn delete_academic_year_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
+    let selected_academic_year = find_academic_year(db, &id).await?;
+    if !academic_year_terms_repo(db, selected_academic_year.id).await?.is_empty() {
+        return Err(SystemError::ValidationError(format!("academic year {} still has terms", selected_academic_year.name)));
+    }
+    if academic_year_sections_count_repo(db, selected_academic_year.id).await? > 0 {
+        return Err(SystemError::ValidationError(format!("academic year {} still has sections", selected_academic_year.name)));
+    }
+
+    match delete_academic_year_repo(db, selected_academic_year).await {
+        Ok(delete_academic_year) => {
+            info!("academic year {} successfully deleted by {}", id, actor.email);
+            Ok(delete_academic_year)
+        }
+        Err(e) => {
+            error!("Failed to delete academic year: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn get_all_academic_years_service(db: &DatabaseConnection) -> Result<Vec<AcademicYearResponseDto>, SystemError> {
+    let academic_years = all_academic_years_repo(db).await?;
+    Ok(academic_years.iter().map(create_response_dto).collect())
+}
+
+
+async fn find_academic_year(db: &DatabaseConnection, id: &str) -> Result<Model, SystemError> {
+    let academic_year_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+    let selected_academic_year = AcademicYear::find_by_id(academic_year_id).one(db).await?;
+    if selected_academic_year.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(selected_academic_year.unwrap())
+}
+
+fn check_dates(dto: &AcademicYearRequestDto) -> Result<(), SystemError> {
+    if dto.start_date >= dto.end_date {
+        return Err(SystemError::ValidationError("an academic year has to start before it ends".to_string()));
+    }
+    Ok(())
+}
+
+fn create_response_dto(academic_year: &Model) -> AcademicYearResponseDto {
+    AcademicYearResponseDto {
+        id: academic_year.id,
+        name: academic_year.name.clone(),
+        start_date: academic_year.start_date,
+        end_date: academic_year.end_date,
+        created_at: academic_year.created_at,
+    }
+}
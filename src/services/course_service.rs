@@ -0,0 +1,177 @@
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{DatabaseConnection, DeleteResult, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::models::{course_model, Course};
+use crate::models::course_model::{CourseRequestDto, CourseResponseDto, CourseStatus, Model, PaginateCourseResponseDto};
+use crate::repo::course_repo::{all_courses_count_repo, all_courses_repo, create_course_repo, delete_course_repo, find_course_by_code, update_course_repo};
+use crate::midleware::permission::{Action, Permission, Resource};
+use crate::repo::teacher_repo::find_teacher_by_user_id;
+use crate::repo::user_repo::find_user_by_email;
+use crate::services::enrollment_service::fill_open_seats_service;
+
+pub async fn create_course_service(db: &DatabaseConnection, dto: CourseRequestDto, actor: &AuthenticatedUser) -> Result<CourseResponseDto, SystemError> {
+    let code = normalize_code(&dto.code);
+    if find_course_by_code(db, &code).await?.is_some() {
+        return Err(SystemError::DuplicateError(code + " course"));
+    }
+    let owner_id = find_owner(db, dto.owner_email).await?;
+
+    let now = Utc::now();
+    let new_course = course_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        code: Set(code),
+        title: Set(dto.title),
+        description: Set(dto.description),
+        credits: Set(dto.credits),
+        owner_id: Set(owner_id),
+        capacity: Set(dto.capacity),
+        status: Set(dto.status.unwrap_or(CourseStatus::Draft)),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    match create_course_repo(db, new_course).await {
+        Ok(course) => {
+            info!("course successfully created by {}: {:?}", actor.email, course);
+            Ok(create_response_dto(&course))
+        }
+        Err(e) => {
+            error!("Failed to create course: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+// draft and archived courses only show to those who manage courses
+pub async fn get_course_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<CourseResponseDto, SystemError> {
+    let selected_course = find_course(db, &id).await?;
+    if selected_course.status != CourseStatus::Published && !can_manage_courses(db, actor).await {
+        return Err(SystemError::NotFoundError(id + " id"));
+    }
+    Ok(create_response_dto(&selected_course))
+}
+
+pub async fn update_course_service(db: &DatabaseConnection, id: String, dto: CourseRequestDto, actor: &AuthenticatedUser) -> Result<CourseResponseDto, SystemError> {
+    let selected_course = find_course(db, &id).await?;
+
+    let code = normalize_code(&dto.code);
+    if code != selected_course.code && find_course_by_code(db, &code).await?.is_some() {
+        return Err(SystemError::DuplicateError(code + " course"));
+    }
+    let owner_id = find_owner(db, dto.owner_email).await?;
+
+    let status = dto.status.unwrap_or(selected_course.status);
+    let mut active_course: course_model::ActiveModel = selected_course.into();
+    active_course.code = Set(code);
+    active_course.title = Set(dto.title);
+    active_course.description = Set(dto.description);
+    active_course.credits = Set(dto.credits);
+    active_course.owner_id = Set(owner_id);
+    active_course.capacity = Set(dto.capacity);
+    active_course.status = Set(status);
+    active_course.updated_at = Set(Utc::now());
+
+    match update_course_repo(db, active_course).await {
+        Ok(course) => {
+            info!("course successfully updated by {}: {:?}", actor.email, course);
+            // a raised capacity frees seats for the waitlist
+            fill_open_seats_service(db, course.id).await?;
+            Ok(create_response_dto(&course))
+        }
+        Err(e) => {
+            error!("Failed to update course: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn delete_course_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
+    let selected_course = find_course(db, &id).await?;
+    match delete_course_repo(db, selected_course).await {
+        Ok(delete_course) => {
+            info!("course {} successfully deleted by {}", id, actor.email);
+            Ok(delete_course)
+        }
+        Err(e) => {
+            error!("Failed to delete course: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn get_all_courses_paginate_service(
+    db: &DatabaseConnection,
+    search_text: String,
+    status: Option<CourseStatus>,
+    page: u64,
+    size: u64,
+    actor: &AuthenticatedUser,
+) -> Result<PaginateCourseResponseDto, SystemError> {
+    let status = if can_manage_courses(db, actor).await {
+        status
+    } else if status.is_none_or(|status| status == CourseStatus::Published) {
+        Some(CourseStatus::Published)
+    } else {
+        return Ok(PaginateCourseResponseDto { count: 0, list: vec![] });
+    };
+    let courses = all_courses_repo(db, &search_text, status, page, size).await?;
+    let courses_count = all_courses_count_repo(db, &search_text, status).await?;
+
+    Ok(PaginateCourseResponseDto {
+        count: courses_count,
+        list: courses.iter().map(create_response_dto).collect(),
+    })
+}
+
+
+async fn can_manage_courses(db: &DatabaseConnection, actor: &AuthenticatedUser) -> bool {
+    actor.holds_permission(db, &Permission::new(Resource::Courses, Action::Write)).await
+}
+
+fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+async fn find_course(db: &DatabaseConnection, id: &str) -> Result<Model, SystemError> {
+    let course_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_course = Course::find_by_id(course_id).one(db).await?;
+    if selected_course.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(selected_course.unwrap())
+}
+
+// the teacher in charge is optional, an unknown email or an account without a teacher profile is an error
+async fn find_owner(db: &DatabaseConnection, owner_email: Option<String>) -> Result<Option<Uuid>, SystemError> {
+    let Some(owner_email) = owner_email else {
+        return Ok(None);
+    };
+    let Some(owner) = find_user_by_email(db, &owner_email).await? else {
+        return Err(SystemError::NotFoundError(owner_email + " user"));
+    };
+    if find_teacher_by_user_id(db, owner.id).await?.is_none() {
+        return Err(SystemError::ValidationError(owner_email + " is not a teacher"));
+    }
+    Ok(Some(owner.id))
+}
+
+fn create_response_dto(course: &Model) -> CourseResponseDto {
+    CourseResponseDto {
+        id: course.id,
+        code: course.code.clone(),
+        title: course.title.clone(),
+        description: course.description.clone(),
+        credits: course.credits,
+        owner_id: course.owner_id,
+        capacity: course.capacity,
+        status: course.status,
+        created_at: course.created_at,
+        updated_at: course.updated_at,
+    }
+}
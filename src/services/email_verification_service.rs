@@ -0,0 +1,121 @@
+use std::env;
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::models::{email_verification_token_model, user_model, User};
+use crate::models::email_verification_token_model::ResendVerificationRequestDto;
+use crate::repo::email_verification_token_repo::{create_email_verification_token_repo, find_email_verification_token_by_hash, invalidate_user_email_verification_tokens_repo, latest_email_verification_token_repo, mark_email_verification_token_used_repo};
+use crate::repo::user_repo::{find_user_by_email, update_user_repo};
+use crate::utill::mailer::{app_link, send_mail, Mail};
+use crate::utill::secure_token::{generate_token, hash_token};
+
+// verification link lifetime, EMAIL_VERIFICATION_TTL_HOURS (default 48)
+fn email_verification_ttl() -> Duration {
+    let hours = env::var("EMAIL_VERIFICATION_TTL_HOURS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(48);
+    Duration::hours(hours)
+}
+
+// minimum time between two verification mails to one account, EMAIL_VERIFICATION_RESEND_SECONDS (default 60)
+fn email_verification_resend_interval() -> Duration {
+    let seconds = env::var("EMAIL_VERIFICATION_RESEND_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(60);
+    Duration::seconds(seconds)
+}
+
+// UNVERIFIED_LOGIN, deny (default) refuses the login, restricted issues tokens that pass no route guard
+pub fn unverified_login_restricted() -> bool {
+    env::var("UNVERIFIED_LOGIN").is_ok_and(|mode| mode == "restricted")
+}
+
+pub async fn send_verification_mail_service(db: &DatabaseConnection, user: &user_model::Model) -> Result<(), SystemError> {
+    invalidate_user_email_verification_tokens_repo(db, user.id).await?;
+
+    let token = generate_token();
+    let now = Utc::now();
+    let new_token = email_verification_token_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        token_hash: Set(hash_token(&token)),
+        expires_at: Set(now + email_verification_ttl()),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    create_email_verification_token_repo(db, new_token).await?;
+
+    let mail = Mail {
+        to: user.email.clone(),
+        subject: "Verify your email address".to_string(),
+        body: format!(
+            "Hi {},\r\n\r\nPlease confirm your email address by opening this link:\r\n\r\n{}\r\n\r\nThe link expires in {} hours.",
+            user.name,
+            app_link(&format!("/verify-email?token={}", token)),
+            email_verification_ttl().num_hours(),
+        ),
+    };
+    send_mail(mail)?;
+
+    info!("verification mail sent to {}", user.email);
+    Ok(())
+}
+
+// always succeeds so the endpoint can't be used to find accounts,
+// unknown, verified and recently mailed accounts get nothing
+pub async fn resend_verification_mail_service(db: &DatabaseConnection, dto: ResendVerificationRequestDto) -> Result<(), SystemError> {
+    let selected_user = find_user_by_email(db, &dto.email).await?;
+    let Some(user) = selected_user.filter(|user| !user.email_verified) else {
+        warn!("verification mail requested for an unknown or verified email: {}", dto.email);
+        return Ok(());
+    };
+
+    let latest = latest_email_verification_token_repo(db, user.id).await?;
+    if latest.is_some_and(|token| token.created_at + email_verification_resend_interval() > Utc::now()) {
+        warn!("verification mail for {} requested again too soon", user.email);
+        return Ok(());
+    }
+
+    if let Err(e) = send_verification_mail_service(db, &user).await {
+        error!("Failed to send verification mail: {:?}", e);
+    }
+    Ok(())
+}
+
+pub async fn verify_email_service(db: &DatabaseConnection, token: String) -> Result<user_model::Model, SystemError> {
+    let selected_token = find_email_verification_token_by_hash(db, &hash_token(&token)).await?;
+    if selected_token.is_none() {
+        return Err(SystemError::ValidationError("Invalid or expired verification token".to_string()));
+    }
+    let selected_token = selected_token.unwrap();
+
+    if selected_token.used_at.is_some() || selected_token.expires_at < Utc::now() {
+        return Err(SystemError::ValidationError("Invalid or expired verification token".to_string()));
+    }
+    if mark_email_verification_token_used_repo(db, selected_token.id).await? == 0 {
+        return Err(SystemError::ValidationError("Invalid or expired verification token".to_string()));
+    }
+
+    let selected_user = User::find_by_id(selected_token.user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(selected_token.user_id.to_string() + " id"));
+    }
+
+    let mut active_user: user_model::ActiveModel = selected_user.unwrap().into();
+    active_user.email_verified = Set(true);
+
+    match update_user_repo(db, active_user).await {
+        Ok(user) => {
+            info!("email verified for user: {}", user.email);
+            Ok(user)
+        }
+        Err(e) => {
+            error!("Failed to verify email: {:?}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, Set, TransactionTrait};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::{Action, Permission, Resource};
+use crate::models::{course_model, enrollment_model, student_model, Course, Student};
+use crate::models::course_model::CourseStatus;
+use crate::models::enrollment_model::{EnrollRequestDto, EnrollmentResponseDto, EnrollmentStatus, EnrollmentStatusRequestDto, Model, MyCourseDto, RosterEntryDto, RosterResponseDto};
+use crate::repo::course_repo::lock_course_repo;
+use crate::repo::enrollment_repo::{course_enrollments_count_repo, course_roster_repo, create_enrollment_repo, find_enrollment, first_waitlisted_repo, student_enrollments_repo, update_enrollment_repo, waitlisted_before_count_repo};
+use crate::repo::student_repo::find_student_by_user_id;
+use crate::repo::term_repo::find_active_term;
+use crate::repo::user_repo::find_users_by_ids;
+use crate::services::term_service::{active_term, term_or_active};
+
+// admins enroll any student in a course that isn't archived
+pub async fn enroll_student_service(db: &DatabaseConnection, course_id: String, dto: EnrollRequestDto, actor: &AuthenticatedUser) -> Result<EnrollmentResponseDto, SystemError> {
+    let course_id = parse_id(&course_id)?;
+    let selected_student = Student::find_by_id(dto.student_id).one(db).await?;
+    if selected_student.is_none() {
+        return Err(SystemError::NotFoundError(dto.student_id.to_string() + " student"));
+    }
+
+    let enrollment = enroll(db, course_id, selected_student.unwrap(), false).await?;
+    info!("student {} enrolled in course {} by {}: {:?}", enrollment.student_id, course_id, actor.email, enrollment.status);
+    create_response_dto(db, &enrollment).await
+}
+
+// students enroll themselves in published courses
+pub async fn enroll_me_service(db: &DatabaseConnection, course_id: String, actor: &AuthenticatedUser) -> Result<EnrollmentResponseDto, SystemError> {
+    let course_id = parse_id(&course_id)?;
+    let student = find_my_student(db, actor).await?;
+
+    let enrollment = enroll(db, course_id, student, true).await?;
+    info!("{} enrolled in course {}: {:?}", actor.email, course_id, enrollment.status);
+    create_response_dto(db, &enrollment).await
+}
+
+pub async fn drop_me_service(db: &DatabaseConnection, course_id: String, actor: &AuthenticatedUser) -> Result<EnrollmentResponseDto, SystemError> {
+    let course_id = parse_id(&course_id)?;
+    let student = find_my_student(db, actor).await?;
+
+    let enrollment = change_status(db, course_id, student.id, None, EnrollmentStatus::Dropped).await?;
+    info!("{} dropped course {}", actor.email, course_id);
+    create_response_dto(db, &enrollment).await
+}
+
+pub async fn set_enrollment_status_service(db: &DatabaseConnection, course_id: String, student_id: String, dto: EnrollmentStatusRequestDto, actor: &AuthenticatedUser) -> Result<EnrollmentResponseDto, SystemError> {
+    let course_id = parse_id(&course_id)?;
+    let student_id = parse_id(&student_id)?;
+
+    let enrollment = change_status(db, course_id, student_id, dto.term_id, dto.status).await?;
+    info!("enrollment of student {} in course {} set to {:?} by {}", student_id, course_id, dto.status, actor.email);
+    create_response_dto(db, &enrollment).await
+}
+
+// hand the open seats of the active term to the waitlist, e.g. after the capacity was raised
+pub async fn fill_open_seats_service(db: &DatabaseConnection, course_id: Uuid) -> Result<(), SystemError> {
+    let txn = db.begin().await?;
+    let course = lock_course_repo(&txn, course_id).await?;
+    let term = find_active_term(&txn).await?;
+    if let (Some(course), Some(term)) = (course, term) {
+        promote_waitlisted(&txn, &course, term.id).await?;
+    }
+    txn.commit().await?;
+    Ok(())
+}
+
+// the course owner and whoever manages courses see who is in the course, in the active term unless another is asked for
+pub async fn get_roster_service(db: &DatabaseConnection, course_id: String, term_id: Option<Uuid>, actor: &AuthenticatedUser) -> Result<RosterResponseDto, SystemError> {
+    let course_id = parse_id(&course_id)?;
+    let selected_course = Course::find_by_id(course_id).one(db).await?;
+    if selected_course.is_none() {
+        return Err(SystemError::NotFoundError(course_id.to_string() + " id"));
+    }
+    let selected_course = selected_course.unwrap();
+    if selected_course.owner_id != Some(actor.user_id) && !actor.holds_permission(db, &Permission::new(Resource::Courses, Action::Write)).await {
+        return Err(SystemError::ForbiddenError("only the teacher of the course can see its roster".to_string()));
+    }
+
+    let (term, _) = term_or_active(db, term_id).await?;
+    let entries = course_roster_repo(db, course_id, term.id).await?;
+    let user_ids = entries.iter().filter_map(|(_, student)| student.as_ref().map(|student| student.user_id)).collect();
+    let users: HashMap<Uuid, _> = find_users_by_ids(db, user_ids).await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+
+    let mut roster = RosterResponseDto {
+        course_id,
+        term_id: term.id,
+        capacity: selected_course.capacity,
+        enrolled: vec![],
+        waitlisted: vec![],
+        completed: vec![],
+    };
+    for (enrollment, student) in entries {
+        let Some(student) = student else { continue };
+        let Some(user) = users.get(&student.user_id) else { continue };
+        let entry = RosterEntryDto {
+            student_id: student.id,
+            user_id: user.id,
+            name: user.name.clone(),
+            email: user.email.clone(),
+            grade: student.grade,
+            status: enrollment.status,
+            status_changed_at: enrollment.status_changed_at,
+        };
+        match enrollment.status {
+            EnrollmentStatus::Enrolled => roster.enrolled.push(entry),
+            EnrollmentStatus::Waitlisted => roster.waitlisted.push(entry),
+            EnrollmentStatus::Completed => roster.completed.push(entry),
+            EnrollmentStatus::Dropped => {}
+        }
+    }
+    Ok(roster)
+}
+
+pub async fn get_my_courses_service(db: &DatabaseConnection, term_id: Option<Uuid>, actor: &AuthenticatedUser) -> Result<Vec<MyCourseDto>, SystemError> {
+    let student = find_my_student(db, actor).await?;
+    let (term, _) = term_or_active(db, term_id).await?;
+
+    let mut courses = vec![];
+    for (enrollment, course) in student_enrollments_repo(db, student.id, term.id).await? {
+        let Some(course) = course else { continue };
+        courses.push(MyCourseDto {
+            course_id: course.id,
+            term_id: enrollment.term_id,
+            code: course.code,
+            title: course.title,
+            credits: course.credits,
+            status: enrollment.status,
+            waitlist_position: waitlist_position(db, &enrollment).await?,
+            status_changed_at: enrollment.status_changed_at,
+        });
+    }
+    Ok(courses)
+}
+
+
+fn parse_id(id: &str) -> Result<Uuid, SystemError> {
+    match Uuid::parse_str(id) {
+        Ok(uuid) => Ok(uuid),
+        Err(_) => Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    }
+}
+
+async fn find_my_student(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<student_model::Model, SystemError> {
+    let student = find_student_by_user_id(db, actor.user_id).await?;
+    if student.is_none() {
+        return Err(SystemError::NotFoundError(actor.email.to_string() + " student"));
+    }
+    Ok(student.unwrap())
+}
+
+// a seat in the active term when one is free, otherwise a place on the waitlist
+async fn enroll(db: &DatabaseConnection, course_id: Uuid, student: student_model::Model, published_only: bool) -> Result<Model, SystemError> {
+    let txn = db.begin().await?;
+    let term = active_term(&txn).await?;
+    let course = lock_course_repo(&txn, course_id).await?;
+    if course.is_none() {
+        return Err(SystemError::NotFoundError(course_id.to_string() + " id"));
+    }
+    let course = course.unwrap();
+    if course.status == CourseStatus::Archived || (published_only && course.status != CourseStatus::Published) {
+        return Err(SystemError::ValidationError(format!("course {} is not open for enrollment", course.code)));
+    }
+
+    let existing = find_enrollment(&txn, course.id, student.id, term.id).await?;
+    if existing.as_ref().is_some_and(|enrollment| enrollment.status != EnrollmentStatus::Dropped) {
+        return Err(SystemError::DuplicateError(format!("enrollment of student {} in {}", student.id, course.code)));
+    }
+
+    let status = if has_open_seat(&txn, &course, term.id).await? { EnrollmentStatus::Enrolled } else { EnrollmentStatus::Waitlisted };
+    let now = Utc::now();
+    let enrollment = match existing {
+        Some(dropped) => {
+            let mut active_enrollment: enrollment_model::ActiveModel = dropped.into();
+            active_enrollment.status = Set(status);
+            active_enrollment.status_changed_at = Set(now);
+            update_enrollment_repo(&txn, active_enrollment).await
+        }
+        None => create_enrollment_repo(&txn, enrollment_model::ActiveModel {
+            course_id: Set(course.id),
+            student_id: Set(student.id),
+            term_id: Set(term.id),
+            status: Set(status),
+            created_at: Set(now),
+            status_changed_at: Set(now),
+        }).await,
+    };
+    let enrollment = match enrollment {
+        Ok(enrollment) => enrollment,
+        Err(e) => {
+            error!("Failed to enroll student: {:?}", e);
+            return Err(e);
+        }
+    };
+    txn.commit().await?;
+    Ok(enrollment)
+}
+
+// enrolled and waitlisted students can drop, enrolled students complete,
+// a freed seat in the active term goes to the waitlist
+async fn change_status(db: &DatabaseConnection, course_id: Uuid, student_id: Uuid, term_id: Option<Uuid>, status: EnrollmentStatus) -> Result<Model, SystemError> {
+    let txn = db.begin().await?;
+    let (term, _) = term_or_active(&txn, term_id).await?;
+    let course = lock_course_repo(&txn, course_id).await?;
+    if course.is_none() {
+        return Err(SystemError::NotFoundError(course_id.to_string() + " id"));
+    }
+    let course = course.unwrap();
+
+    let enrollment = find_enrollment(&txn, course_id, student_id, term.id).await?;
+    if enrollment.is_none() {
+        return Err(SystemError::NotFoundError(format!("enrollment of student {} in {}", student_id, course.code)));
+    }
+    let enrollment = enrollment.unwrap();
+
+    let allowed = match status {
+        EnrollmentStatus::Dropped => matches!(enrollment.status, EnrollmentStatus::Enrolled | EnrollmentStatus::Waitlisted),
+        EnrollmentStatus::Completed => enrollment.status == EnrollmentStatus::Enrolled,
+        EnrollmentStatus::Enrolled | EnrollmentStatus::Waitlisted => false,
+    };
+    if !allowed {
+        return Err(SystemError::ValidationError(format!("a {:?} enrollment can't become {:?}", enrollment.status, status)));
+    }
+
+    let freed_seat = enrollment.status == EnrollmentStatus::Enrolled;
+    let mut active_enrollment: enrollment_model::ActiveModel = enrollment.into();
+    active_enrollment.status = Set(status);
+    active_enrollment.status_changed_at = Set(Utc::now());
+    let enrollment = update_enrollment_repo(&txn, active_enrollment).await?;
+
+    if freed_seat && term.is_active {
+        promote_waitlisted(&txn, &course, term.id).await?;
+    }
+    txn.commit().await?;
+    Ok(enrollment)
+}
+
+// the capacity holds per term
+async fn has_open_seat<C: ConnectionTrait>(db: &C, course: &course_model::Model, term_id: Uuid) -> Result<bool, SystemError> {
+    match course.capacity {
+        Some(capacity) => Ok(course_enrollments_count_repo(db, course.id, term_id, EnrollmentStatus::Enrolled).await? < capacity as u64),
+        None => Ok(true),
+    }
+}
+
+// the course row has to be locked by the caller
+async fn promote_waitlisted<C: ConnectionTrait>(db: &C, course: &course_model::Model, term_id: Uuid) -> Result<(), SystemError> {
+    while has_open_seat(db, course, term_id).await? {
+        let Some(next) = first_waitlisted_repo(db, course.id, term_id).await? else { break };
+        let student_id = next.student_id;
+        let mut active_enrollment: enrollment_model::ActiveModel = next.into();
+        active_enrollment.status = Set(EnrollmentStatus::Enrolled);
+        active_enrollment.status_changed_at = Set(Utc::now());
+        update_enrollment_repo(db, active_enrollment).await?;
+        info!("student {} promoted from the waitlist of course {}", student_id, course.code);
+    }
+    Ok(())
+}
+
+async fn waitlist_position(db: &DatabaseConnection, enrollment: &Model) -> Result<Option<u64>, SystemError> {
+    if enrollment.status != EnrollmentStatus::Waitlisted {
+        return Ok(None);
+    }
+    Ok(Some(waitlisted_before_count_repo(db, enrollment).await? + 1))
+}
+
+async fn create_response_dto(db: &DatabaseConnection, enrollment: &Model) -> Result<EnrollmentResponseDto, SystemError> {
+    Ok(EnrollmentResponseDto {
+        course_id: enrollment.course_id,
+        student_id: enrollment.student_id,
+        term_id: enrollment.term_id,
+        status: enrollment.status,
+        waitlist_position: waitlist_position(db, enrollment).await?,
+        created_at: enrollment.created_at,
+        status_changed_at: enrollment.status_changed_at,
+    })
+}
@@ -0,0 +1,145 @@
+use std::env;
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::models::{lockout_event_model, user_model, User};
+use crate::models::lockout_event_model::PaginateLockoutEventResponseDto;
+use crate::repo::lockout_event_repo::{all_lockout_events_count_repo, all_lockout_events_repo, create_lockout_event_repo};
+use crate::repo::user_repo::{extend_locked_until_repo, increment_failed_logins_repo, update_user_repo};
+
+/*
+Login throttling per account
+
+LOGIN_BACKOFF_AFTER     failures before each further attempt has to wait, 1s doubling every failure (default 3)
+LOGIN_MAX_FAILURES      failures before the account is locked (default 10)
+LOGIN_LOCKOUT_MINUTES   how long a locked account stays locked (default 15)
+*/
+
+fn env_number(name: &str, default: i64) -> i64 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+// rejects the login without checking the password while the account waits or is locked
+pub fn check_login_allowed(user: &user_model::Model) -> Result<(), SystemError> {
+    match user.locked_until {
+        Some(locked_until) if locked_until > Utc::now() => {
+            let retry_after = (locked_until - Utc::now()).num_seconds() + 1;
+            warn!("login blocked for {} for {} seconds", user.email, retry_after);
+            Err(SystemError::AccountLocked(retry_after))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub async fn record_login_failure(db: &DatabaseConnection, user: user_model::Model, ip_address: Option<String>) -> Result<(), SystemError> {
+    // the count comes back from the increment itself, the loaded row may be stale
+    let user = match increment_failed_logins_repo(db, user.id).await? {
+        Some(user) => user,
+        None => return Err(SystemError::NotFoundError(user.id.to_string() + " id")),
+    };
+    let failed_attempts = user.failed_login_attempts;
+    let backoff_after = env_number("LOGIN_BACKOFF_AFTER", 3);
+    let max_failures = env_number("LOGIN_MAX_FAILURES", 10);
+
+    let now = Utc::now();
+    let locked = failed_attempts as i64 >= max_failures;
+    let locked_until = if locked {
+        Some(now + Duration::minutes(env_number("LOGIN_LOCKOUT_MINUTES", 15)))
+    } else if failed_attempts as i64 >= backoff_after {
+        // 1s, 2s, 4s ... between attempts until the lockout kicks in
+        let exponent = (failed_attempts as i64 - backoff_after).min(20) as u32;
+        Some(now + Duration::seconds(2i64.pow(exponent)))
+    } else {
+        None
+    };
+
+    let user_id = user.id;
+    let email = user.email.clone();
+    if let Some(locked_until) = locked_until {
+        extend_locked_until_repo(db, user_id, locked_until).await?;
+    }
+
+    if locked {
+        let event = lockout_event_model::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            email: Set(email.clone()),
+            event: Set("locked".to_string()),
+            failed_attempts: Set(failed_attempts),
+            locked_until: Set(locked_until),
+            ip_address: Set(ip_address),
+            actor: Set(None),
+            created_at: Set(now),
+        };
+        create_lockout_event_repo(db, event).await?;
+        warn!("account locked after {} failed logins: {}", failed_attempts, email);
+    } else {
+        warn!("failed login {} for {}", failed_attempts, email);
+    }
+    Ok(())
+}
+
+pub async fn reset_login_failures(db: &DatabaseConnection, user: user_model::Model) -> Result<user_model::Model, SystemError> {
+    if user.failed_login_attempts == 0 && user.locked_until.is_none() {
+        return Ok(user);
+    }
+
+    let mut active_user: user_model::ActiveModel = user.into();
+    active_user.failed_login_attempts = Set(0);
+    active_user.locked_until = Set(None);
+    update_user_repo(db, active_user).await
+}
+
+pub async fn unlock_user_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<user_model::Model, SystemError> {
+    let user_id = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    let selected_user = selected_user.unwrap();
+    let failed_attempts = selected_user.failed_login_attempts;
+
+    let user = match reset_login_failures(db, selected_user).await {
+        Ok(user) => user,
+        Err(e) => {
+            error!("Failed to unlock user: {:?}", e);
+            return Err(e);
+        }
+    };
+
+    let event = lockout_event_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        email: Set(user.email.clone()),
+        event: Set("unlocked".to_string()),
+        failed_attempts: Set(failed_attempts),
+        locked_until: Set(None),
+        ip_address: Set(None),
+        actor: Set(Some(actor.email.clone())),
+        created_at: Set(Utc::now()),
+    };
+    create_lockout_event_repo(db, event).await?;
+
+    info!("user {} unlocked by {}", user.email, actor.email);
+    Ok(user)
+}
+
+pub async fn get_lockout_events_service(db: &DatabaseConnection, page: u64, size: u64) -> Result<PaginateLockoutEventResponseDto, SystemError> {
+    let events = all_lockout_events_repo(db, page, size).await?;
+    let events_count = all_lockout_events_count_repo(db).await?;
+
+    Ok(PaginateLockoutEventResponseDto {
+        count: events_count,
+        list: events,
+    })
+}
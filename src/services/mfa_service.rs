@@ -0,0 +1,187 @@
+use std::env;
+use chrono::Utc;
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::{JwtError, PasswordError, SystemError};
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::{recovery_code_model, revoked_token_model, user_model, User};
+use crate::models::recovery_code_model::{MfaChallengeResponseDto, MfaConfirmRequestDto, MfaEnrollResponseDto, MfaLoginRequestDto, RecoveryCodesResponseDto};
+use crate::models::refresh_token_model::TokenResponseDto;
+use crate::repo::recovery_code_repo::{create_recovery_codes_repo, delete_user_recovery_codes_repo, find_unused_recovery_code, mark_recovery_code_used_repo};
+use crate::repo::revoked_token_repo::{create_revoked_token_repo, find_revoked_token_by_jti};
+use crate::repo::user_repo::{claim_totp_step_repo, update_user_repo};
+use crate::services::login_throttle_service::{check_login_allowed, record_login_failure, reset_login_failures};
+use crate::services::session_service::start_session_service;
+use crate::utill::jwt::{create_token, mfa_challenge_ttl, verify_mfa_challenge_token, MfaChallengeClaims};
+use crate::utill::secure_token::{generate_token, hash_token};
+use crate::utill::totp::{generate_secret, otpauth_uri, verify_code};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+// start enrollment, the secret is only used after it was confirmed with a code
+pub async fn enroll_mfa_service(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<MfaEnrollResponseDto, SystemError> {
+    let selected_user = find_user(db, actor.user_id).await?;
+    if selected_user.totp_enabled {
+        return Err(SystemError::ValidationError("Two-factor authentication is already enabled".to_string()));
+    }
+
+    let secret = generate_secret();
+    let issuer = env::var("MFA_ISSUER").unwrap_or("ST-LMS".to_string());
+    let uri = otpauth_uri(&issuer, &selected_user.email, &secret);
+
+    let mut active_user: user_model::ActiveModel = selected_user.into();
+    active_user.totp_secret = Set(Some(secret.clone()));
+    active_user.totp_last_step = Set(None);
+    update_user_repo(db, active_user).await?;
+
+    info!("two-factor enrollment started for {}", actor.email);
+    Ok(MfaEnrollResponseDto {
+        secret,
+        otpauth_uri: uri,
+    })
+}
+
+// enable two-factor authentication with the first code, returns the recovery codes once
+pub async fn confirm_mfa_service(db: &DatabaseConnection, dto: MfaConfirmRequestDto, actor: &AuthenticatedUser) -> Result<RecoveryCodesResponseDto, SystemError> {
+    let selected_user = find_user(db, actor.user_id).await?;
+    if selected_user.totp_enabled {
+        return Err(SystemError::ValidationError("Two-factor authentication is already enabled".to_string()));
+    }
+    let secret = match &selected_user.totp_secret {
+        Some(secret) => secret.clone(),
+        None => return Err(SystemError::ValidationError("Two-factor enrollment has not been started".to_string())),
+    };
+
+    let step = match verify_code(&secret, &dto.code, None) {
+        Some(step) => step,
+        None => return Err(SystemError::PasswordError(PasswordError::InvalidMfaCode)),
+    };
+
+    let user_id = selected_user.id;
+    let mut active_user: user_model::ActiveModel = selected_user.into();
+    active_user.totp_enabled = Set(true);
+    active_user.totp_last_step = Set(Some(step));
+    update_user_repo(db, active_user).await?;
+
+    let recovery_codes = create_recovery_codes(db, user_id).await?;
+    info!("two-factor authentication enabled for {}", actor.email);
+    Ok(RecoveryCodesResponseDto { recovery_codes })
+}
+
+// first login step for accounts with two-factor authentication
+pub fn create_mfa_challenge(user: &user_model::Model) -> Result<MfaChallengeResponseDto, SystemError> {
+    let claims = MfaChallengeClaims::new(user.id, user.email.clone());
+    match create_token(&claims) {
+        Ok(mfa_token) => Ok(MfaChallengeResponseDto {
+            mfa_required: true,
+            mfa_token,
+            expires_in: mfa_challenge_ttl().num_seconds(),
+        }),
+        Err(e) => {
+            error!("mfa challenge not created {:?}", e);
+            Err(SystemError::JwtError(JwtError::TokenError("Failed to generate token : ".to_string())))
+        }
+    }
+}
+
+// second login step, exchanges the challenge and a TOTP or recovery code for tokens
+pub async fn mfa_login_service(db: &DatabaseConnection, dto: MfaLoginRequestDto, client: ClientInfo) -> Result<TokenResponseDto, SystemError> {
+    let claims = match verify_mfa_challenge_token(&dto.mfa_token) {
+        Ok(token) => token.claims,
+        Err(e) => return Err(SystemError::JwtError(JwtError::TokenError(e.to_string()))),
+    };
+    // a challenge can only be used once
+    if find_revoked_token_by_jti(db, &claims.jti).await?.is_some() {
+        return Err(SystemError::JwtError(JwtError::TokenError("MFA token has already been used".to_string())));
+    }
+
+    let selected_user = find_user(db, claims.uid).await?;
+    check_login_allowed(&selected_user)?;
+    if !selected_user.totp_enabled {
+        return Err(SystemError::JwtError(JwtError::TokenError("Two-factor authentication is not enabled".to_string())));
+    }
+
+    let code = dto.code.trim();
+    let accepted = if code.len() == 6 {
+        match verify_code(selected_user.totp_secret.as_deref().unwrap_or_default(), code, selected_user.totp_last_step) {
+            Some(step) => claim_totp_step_repo(db, selected_user.id, step).await?,
+            None => false,
+        }
+    } else {
+        use_recovery_code(db, &selected_user, code).await?
+    };
+
+    if !accepted {
+        warn!("invalid two-factor code for {}", selected_user.email);
+        record_login_failure(db, selected_user, client.ip_address).await?;
+        return Err(SystemError::PasswordError(PasswordError::InvalidMfaCode));
+    }
+
+    let challenge = revoked_token_model::ActiveModel {
+        jti: Set(claims.jti),
+        subject: Set(claims.sub),
+        expires_at: Set(Utc::now() + mfa_challenge_ttl()),
+        revoked_at: Set(Utc::now()),
+    };
+    create_revoked_token_repo(db, challenge).await?;
+
+    let selected_user = find_user(db, selected_user.id).await?;
+    let selected_user = reset_login_failures(db, selected_user).await?;
+    let tokens = start_session_service(db, &selected_user, client).await?;
+    info!("token created successfully for user: {} (two-factor)", selected_user.email);
+    Ok(tokens)
+}
+
+
+async fn find_user(db: &DatabaseConnection, user_id: Uuid) -> Result<user_model::Model, SystemError> {
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(user_id.to_string() + " id"));
+    }
+    Ok(selected_user.unwrap())
+}
+
+// recovery codes look like 1a2b3-c4d5e, the dash and case are optional when typed in
+fn normalize_recovery_code(code: &str) -> String {
+    code.trim().replace('-', "").to_lowercase()
+}
+
+async fn create_recovery_codes(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<String>, SystemError> {
+    delete_user_recovery_codes_repo(db, user_id).await?;
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let token = generate_token();
+            format!("{}-{}", &token[..5], &token[5..10])
+        })
+        .collect();
+    let now = Utc::now();
+    let models = codes
+        .iter()
+        .map(|code| recovery_code_model::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            code_hash: Set(hash_token(&normalize_recovery_code(code))),
+            created_at: Set(now),
+            used_at: Set(None),
+        })
+        .collect();
+    create_recovery_codes_repo(db, models).await?;
+    Ok(codes)
+}
+
+async fn use_recovery_code(db: &DatabaseConnection, user: &user_model::Model, code: &str) -> Result<bool, SystemError> {
+    let recovery_code = find_unused_recovery_code(db, user.id, &hash_token(&normalize_recovery_code(code))).await?;
+    match recovery_code {
+        Some(recovery_code) => {
+            let used = mark_recovery_code_used_repo(db, recovery_code.id).await? == 1;
+            if used {
+                warn!("recovery code used by {}", user.email);
+            }
+            Ok(used)
+        }
+        None => Ok(false),
+    }
+}
@@ -1,2 +1,19 @@
 pub mod user_service;
-pub mod student_service;
\ No newline at end of file
+pub mod student_service;
+pub mod token_service;
+pub mod role_service;
+pub mod password_service;
+pub mod email_verification_service;
+pub mod login_throttle_service;
+pub mod mfa_service;
+pub mod service_account_service;
+pub mod oidc_service;
+
+pub mod session_service;
+pub mod user_role_service;
+pub mod course_service;
+pub mod enrollment_service;
+pub mod teacher_service;
+pub mod section_service;
+pub mod academic_year_service;
+pub mod term_service;
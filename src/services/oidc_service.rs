@@ -0,0 +1,172 @@
+use std::env;
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::{PasswordError, SystemError};
+use crate::midleware::client_info::ClientInfo;
+use crate::midleware::permission::Role;
+use crate::models::{oidc_login_state_model, user_model};
+use crate::models::oidc_login_state_model::OidcCallbackQuery;
+use crate::models::refresh_token_model::TokenResponseDto;
+use crate::models::user_model::UserRole;
+use crate::repo::oidc_login_state_repo::{create_oidc_login_state_repo, delete_expired_oidc_login_states_repo, find_oidc_login_state_by_hash, mark_oidc_login_state_used_repo};
+use crate::repo::user_repo::{create_user_repo, find_user_by_email, update_user_repo};
+use crate::services::session_service::start_session_service;
+use crate::services::user_service::assign_role;
+use crate::utill::oidc::{authorization_url, default_role, exchange_code, oidc_config, pkce_challenge, role_for_groups, verify_id_token, OidcConfig, OidcIdentity};
+use crate::utill::password_hash::hash_password;
+use crate::utill::secure_token::{generate_token, hash_token};
+
+// time to finish the sign-in at the provider, OIDC_STATE_TTL_MINUTES (default 10)
+fn login_state_ttl() -> Duration {
+    let minutes = env::var("OIDC_STATE_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(10);
+    Duration::minutes(minutes)
+}
+
+fn config() -> Result<OidcConfig, SystemError> {
+    oidc_config().ok_or(SystemError::NotFoundError("single sign-on configuration".to_string()))
+}
+
+// start a sign-in, returns the provider url the browser is sent to
+pub async fn oidc_login_service(db: &DatabaseConnection) -> Result<String, SystemError> {
+    let config = config()?;
+
+    let state = generate_token();
+    let nonce = generate_token();
+    let code_verifier = generate_token();
+    let url = authorization_url(&config, &state, &nonce, &pkce_challenge(&code_verifier)).await?;
+
+    let now = Utc::now();
+    let login_state = oidc_login_state_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        state_hash: Set(hash_token(&state)),
+        code_verifier: Set(code_verifier),
+        nonce: Set(nonce),
+        expires_at: Set(now + login_state_ttl()),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    create_oidc_login_state_repo(db, login_state).await?;
+    delete_expired_oidc_login_states_repo(db).await?;
+
+    Ok(url)
+}
+
+// finish a sign-in, the provider redirected back with the code
+pub async fn oidc_callback_service(db: &DatabaseConnection, query: OidcCallbackQuery, client: ClientInfo) -> Result<TokenResponseDto, SystemError> {
+    let config = config()?;
+
+    let login_state = find_oidc_login_state_by_hash(db, &hash_token(&query.state)).await?;
+    let login_state = match login_state {
+        Some(login_state) if login_state.used_at.is_none() && login_state.expires_at > Utc::now() => login_state,
+        _ => return Err(SystemError::OidcError("unknown or expired sign-in".to_string())),
+    };
+    // a state is good for one callback, whatever its outcome
+    if mark_oidc_login_state_used_repo(db, login_state.id).await? == 0 {
+        return Err(SystemError::OidcError("unknown or expired sign-in".to_string()));
+    }
+
+    if let Some(e) = query.error {
+        warn!("sign-in refused by the identity provider: {} {}", e, query.error_description.unwrap_or_default());
+        return Err(SystemError::OidcError(e));
+    }
+    let code = query.code.ok_or(SystemError::OidcError("no authorization code".to_string()))?;
+
+    let id_token = exchange_code(&config, &code, &login_state.code_verifier).await?;
+    let identity = verify_id_token(&config, &id_token, &login_state.nonce).await?;
+
+    let user = find_or_provision_user(db, identity).await?;
+
+    let tokens = start_session_service(db, &user, client).await?;
+    info!("token created successfully for single sign-on user: {}", user.email);
+    Ok(tokens)
+}
+
+
+// the local account with the email of the identity, created on the first sign-in,
+// a mapped group sets the role on every sign-in so changes at the provider carry over,
+// accounts with two-factor authentication are never linked
+async fn find_or_provision_user(db: &DatabaseConnection, identity: OidcIdentity) -> Result<user_model::Model, SystemError> {
+    let mapped_role = role_for_groups(&identity.groups).and_then(|role| storable_role(role, &identity.email));
+
+    if let Some(user) = find_user_by_email(db, &identity.email).await? {
+        // the provider only proves the email, that's not enough to skip a second factor
+        if user.totp_enabled {
+            warn!("single sign-on refused for {}, the account has two-factor authentication", user.email);
+            return Err(SystemError::OidcError("this account uses two-factor authentication, sign in with your password".to_string()));
+        }
+        let mut active_user: user_model::ActiveModel = user.clone().into();
+        let mut changed = false;
+        // the provider vouches for the address
+        if !user.email_verified {
+            active_user.email_verified = Set(true);
+            changed = true;
+        }
+        let user = if changed { update_user_repo(db, active_user).await? } else { user };
+
+        return match mapped_role.filter(|role| *role != user.role) {
+            Some(role) => {
+                info!("role of {} changed from {} to {} by identity provider groups", user.email, user.role.as_str(), role.as_str());
+                assign_role(db, user, role).await
+            }
+            None => Ok(user),
+        };
+    }
+
+    let role = match mapped_role.or(default_role().and_then(|role| storable_role(role, &identity.email))) {
+        Some(role) => role,
+        None => {
+            warn!("single sign-on refused, no role for the groups of {}: {:?}", identity.email, identity.groups);
+            return Err(SystemError::OidcError("no role is mapped to your groups".to_string()));
+        }
+    };
+
+    // the account signs in at the provider, nobody knows this password
+    let hash_pw = match hash_password(&generate_token()) {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Password hashing failed: {:?}", e);
+            return Err(SystemError::PasswordError(PasswordError::PasswordHashErr(e.to_string())));
+        }
+    };
+
+    let name = identity.name.unwrap_or_else(|| identity.email.split('@').next().unwrap_or_default().to_string());
+    let new_user = user_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(name),
+        email: Set(identity.email),
+        role: Set(role),
+        password: Set(hash_pw),
+        email_verified: Set(true),
+        failed_login_attempts: Set(0),
+        locked_until: Set(None),
+        totp_secret: Set(None),
+        totp_enabled: Set(false),
+        totp_last_step: Set(None),
+        tokens_revoked_at: Set(None),
+    };
+
+    match create_user_repo(db, new_user).await {
+        Ok(user) => {
+            info!("User provisioned by single sign-on: {} (subject {})", user.email, identity.subject);
+            Ok(user)
+        }
+        Err(e) => {
+            error!("Failed to provision user: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+// users hold one of the built-in roles, custom roles are only granted through the roles table
+fn storable_role(role: Role, email: &str) -> Option<UserRole> {
+    let user_role = UserRole::from_role(&role);
+    if user_role.is_none() {
+        warn!("OIDC role {} for {} is not a built-in role, ignored", role.as_str(), email);
+    }
+    user_role
+}
@@ -0,0 +1,119 @@
+use std::env;
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::{PasswordError, SystemError};
+use crate::models::{password_reset_token_model, user_model, User};
+use crate::models::password_reset_token_model::{ForgotPasswordRequestDto, ResetPasswordRequestDto};
+use crate::repo::password_reset_token_repo::{create_password_reset_token_repo, find_password_reset_token_by_hash, invalidate_user_password_reset_tokens_repo, mark_password_reset_token_used_repo};
+use crate::repo::refresh_token_repo::revoke_user_refresh_tokens_repo;
+use crate::repo::session_repo::revoke_user_sessions_repo;
+use crate::repo::user_repo::{find_user_by_email, update_user_repo};
+use crate::utill::password_hash::hash_password;
+use crate::utill::password_policy::validate_password;
+use crate::utill::mailer::{password_reset_link, send_mail, Mail};
+use crate::utill::secure_token::{generate_token, hash_token};
+
+// reset link lifetime, PASSWORD_RESET_TTL_MINUTES (default 30)
+fn password_reset_ttl() -> Duration {
+    let minutes = env::var("PASSWORD_RESET_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(30);
+    Duration::minutes(minutes)
+}
+
+// always succeeds for unknown emails so the endpoint can't be used to find accounts
+pub async fn forgot_password_service(db: &DatabaseConnection, dto: ForgotPasswordRequestDto) -> Result<(), SystemError> {
+    let selected_user = find_user_by_email(db, &dto.email).await?;
+    if selected_user.is_none() {
+        warn!("password reset requested for unknown email: {}", dto.email);
+        return Ok(());
+    }
+    let selected_user = selected_user.unwrap();
+
+    invalidate_user_password_reset_tokens_repo(db, selected_user.id).await?;
+
+    let token = generate_token();
+    let now = Utc::now();
+    let new_token = password_reset_token_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(selected_user.id),
+        token_hash: Set(hash_token(&token)),
+        expires_at: Set(now + password_reset_ttl()),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    create_password_reset_token_repo(db, new_token).await?;
+
+    let mail = Mail {
+        to: selected_user.email.clone(),
+        subject: "Reset your password".to_string(),
+        body: format!(
+            "Hi {},\r\n\r\nUse this token to reset your password, it expires in {} minutes:\r\n\r\n{}\r\n\r\n{}If you didn't ask for a password reset you can ignore this mail.",
+            selected_user.name,
+            password_reset_ttl().num_minutes(),
+            token,
+            password_reset_link(&token).map(|link| link + "\r\n\r\n").unwrap_or_default(),
+        ),
+    };
+    match send_mail(mail) {
+        Ok(_) => info!("password reset mail sent to {}", selected_user.email),
+        Err(e) => error!("Failed to send password reset mail: {:?}", e),
+    }
+    Ok(())
+}
+
+pub async fn reset_password_service(db: &DatabaseConnection, dto: ResetPasswordRequestDto) -> Result<(), SystemError> {
+    let token = find_password_reset_token_by_hash(db, &hash_token(&dto.token)).await?;
+    if token.is_none() {
+        return Err(SystemError::PasswordError(PasswordError::InvalidResetToken));
+    }
+    let token = token.unwrap();
+
+    if token.used_at.is_some() || token.expires_at < Utc::now() {
+        return Err(SystemError::PasswordError(PasswordError::InvalidResetToken));
+    }
+
+    let selected_user = User::find_by_id(token.user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(token.user_id.to_string() + " id"));
+    }
+    let selected_user = selected_user.unwrap();
+
+    // a rejected password leaves the token usable for another try
+    validate_password(&dto.new_password, &selected_user.name, &selected_user.email).map_err(SystemError::FieldValidationError)?;
+
+    // two requests with the same token, only the first one wins
+    if mark_password_reset_token_used_repo(db, token.id).await? == 0 {
+        return Err(SystemError::PasswordError(PasswordError::InvalidResetToken));
+    }
+
+    let hash_pw = match hash_password(&dto.new_password) {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Password hashing failed: {:?}", e);
+            return Err(SystemError::PasswordError(PasswordError::PasswordHashErr(e.to_string())));
+        }
+    };
+
+    // sessions opened with the old password are ended
+    revoke_user_refresh_tokens_repo(db, selected_user.id).await?;
+    revoke_user_sessions_repo(db, selected_user.id).await?;
+
+    let mut active_user: user_model::ActiveModel = selected_user.into();
+    active_user.password = Set(hash_pw);
+    active_user.tokens_revoked_at = Set(Some(Utc::now()));
+
+    match update_user_repo(db, active_user).await {
+        Ok(user) => {
+            info!("password reset for user: {}", user.email);
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to reset password: {:?}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{DatabaseConnection, Set};
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::Role;
+use crate::models::{role_model, role_permission_model};
+use crate::models::role_model::{RoleRequestDto, RoleResponseDto};
+use crate::repo::role_repo::{all_role_permissions_repo, all_roles_repo, create_role_permission_repo, create_role_repo, delete_role_permission_repo, find_permission_by_name, find_role_by_name, find_role_permission};
+
+type RolePermissionMap = HashMap<String, HashSet<String>>;
+
+struct CachedRolePermissions {
+    loaded_at: Instant,
+    roles: Arc<RolePermissionMap>,
+}
+
+// role name -> permission names, reloaded after a change or when the ttl runs out
+static ROLE_PERMISSIONS: LazyLock<RwLock<Option<CachedRolePermissions>>> = LazyLock::new(|| RwLock::new(None));
+
+
+// cache lifetime, ROLE_CACHE_TTL_SECONDS (default 60), picks up changes made by other instances
+fn cache_ttl() -> Duration {
+    let seconds = env::var("ROLE_CACHE_TTL_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(60);
+    Duration::from_secs(seconds)
+}
+
+pub async fn role_permissions(db: &DatabaseConnection) -> Result<Arc<RolePermissionMap>, SystemError> {
+    if let Some(cached) = ROLE_PERMISSIONS.read().unwrap().as_ref() {
+        if cached.loaded_at.elapsed() < cache_ttl() {
+            return Ok(cached.roles.clone());
+        }
+    }
+
+    let mut roles: RolePermissionMap = all_roles_repo(db)
+        .await?
+        .into_iter()
+        .map(|role| (role.name, HashSet::new()))
+        .collect();
+    for role_permission in all_role_permissions_repo(db).await? {
+        roles.entry(role_permission.role_name).or_default().insert(role_permission.permission_name);
+    }
+
+    let roles = Arc::new(roles);
+    *ROLE_PERMISSIONS.write().unwrap() = Some(CachedRolePermissions {
+        loaded_at: Instant::now(),
+        roles: roles.clone(),
+    });
+    Ok(roles)
+}
+
+pub fn invalidate_role_permissions() {
+    *ROLE_PERMISSIONS.write().unwrap() = None;
+}
+
+
+pub async fn get_all_roles_service(db: &DatabaseConnection) -> Result<Vec<RoleResponseDto>, SystemError> {
+    let roles = all_roles_repo(db).await?;
+    let role_permission = role_permissions(db).await?;
+
+    Ok(roles.iter().map(|role| create_response_dto(role, &role_permission)).collect())
+}
+
+pub async fn create_role_service(db: &DatabaseConnection, dto: RoleRequestDto, actor: &AuthenticatedUser) -> Result<RoleResponseDto, SystemError> {
+    if find_role_by_name(db, &dto.name).await?.is_some() {
+        return Err(SystemError::DuplicateError(dto.name + " role"));
+    }
+
+    for permission in &dto.permissions {
+        if find_permission_by_name(db, permission).await?.is_none() {
+            return Err(SystemError::NotFoundError(permission.to_string() + " permission"));
+        }
+    }
+
+    let new_role = role_model::ActiveModel {
+        name: Set(dto.name.clone()),
+        description: Set(dto.description),
+        built_in: Set(false),
+        created_at: Set(Utc::now()),
+    };
+    let role = match create_role_repo(db, new_role).await {
+        Ok(role) => role,
+        Err(e) => {
+            error!("Failed to create role: {:?}", e);
+            return Err(e);
+        }
+    };
+
+    for permission in dto.permissions {
+        let role_permission = role_permission_model::ActiveModel {
+            role_name: Set(role.name.clone()),
+            permission_name: Set(permission),
+        };
+        create_role_permission_repo(db, role_permission).await?;
+    }
+    invalidate_role_permissions();
+
+    info!("role {} successfully created by {}", role.name, actor.email);
+    Ok(create_response_dto(&role, &*role_permissions(db).await?))
+}
+
+pub async fn grant_permission_service(db: &DatabaseConnection, role_name: String, permission_name: String, actor: &AuthenticatedUser) -> Result<RoleResponseDto, SystemError> {
+    let role = find_role_by_name(db, &role_name).await?;
+    if role.is_none() {
+        return Err(SystemError::NotFoundError(role_name + " role"));
+    }
+    let role = role.unwrap();
+
+    if find_permission_by_name(db, &permission_name).await?.is_none() {
+        return Err(SystemError::NotFoundError(permission_name + " permission"));
+    }
+    if find_role_permission(db, &role_name, &permission_name).await?.is_some() {
+        return Err(SystemError::DuplicateError(permission_name + " permission of " + &role_name));
+    }
+
+    let role_permission = role_permission_model::ActiveModel {
+        role_name: Set(role_name),
+        permission_name: Set(permission_name.clone()),
+    };
+    create_role_permission_repo(db, role_permission).await?;
+    invalidate_role_permissions();
+
+    info!("permission {} granted to role {} by {}", permission_name, role.name, actor.email);
+    Ok(create_response_dto(&role, &*role_permissions(db).await?))
+}
+
+pub async fn revoke_permission_service(db: &DatabaseConnection, role_name: String, permission_name: String, actor: &AuthenticatedUser) -> Result<RoleResponseDto, SystemError> {
+    // admins must always be able to manage roles, otherwise nobody can undo the change
+    if role_name == Role::Admin.as_str() {
+        return Err(SystemError::ValidationError("Admin role permissions can not be revoked".to_string()));
+    }
+
+    let role = find_role_by_name(db, &role_name).await?;
+    if role.is_none() {
+        return Err(SystemError::NotFoundError(role_name + " role"));
+    }
+    let role = role.unwrap();
+
+    let role_permission = find_role_permission(db, &role_name, &permission_name).await?;
+    if role_permission.is_none() {
+        return Err(SystemError::NotFoundError(permission_name + " permission of " + &role_name));
+    }
+
+    delete_role_permission_repo(db, role_permission.unwrap()).await?;
+    invalidate_role_permissions();
+
+    info!("permission {} revoked from role {} by {}", permission_name, role.name, actor.email);
+    Ok(create_response_dto(&role, &*role_permissions(db).await?))
+}
+
+
+fn create_response_dto(role: &role_model::Model, role_permission: &RolePermissionMap) -> RoleResponseDto {
+    let mut permissions: Vec<String> = role_permission
+        .get(&role.name)
+        .map(|permissions| permissions.iter().cloned().collect())
+        .unwrap_or_default();
+    permissions.sort();
+
+    RoleResponseDto {
+        name: role.name.clone(),
+        description: role.description.clone(),
+        built_in: role.built_in,
+        permissions,
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{DatabaseConnection, DeleteResult, EntityTrait, Set, TransactionTrait};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::models::{academic_year_model, section_model, student_term_model, AcademicYear, Section, Student, Teacher};
+use crate::models::section_model::{Model, MoveStudentsRequestDto, PaginateSectionResponseDto, SectionRequestDto, SectionResponseDto, SectionStudentDto};
+use crate::models::student_model::StudentResponseDto;
+use crate::repo::section_repo::{all_sections_count_repo, all_sections_repo, create_section_repo, delete_section_repo, find_section_by_name, find_students_by_ids, move_students_repo, remove_student_from_section_repo, update_section_repo};
+use crate::repo::student_term_repo::{find_student_term, save_student_term_repo, section_student_terms_repo};
+use crate::repo::user_repo::find_users_by_ids;
+use crate::services::student_service::create_response_dto as create_student_response_dto;
+use crate::services::term_service::term_or_active;
+
+pub async fn create_section_service(db: &DatabaseConnection, dto: SectionRequestDto, actor: &AuthenticatedUser) -> Result<SectionResponseDto, SystemError> {
+    let section_letter = dto.section_letter.to_uppercase();
+    let academic_year = find_academic_year(db, dto.academic_year_id).await?;
+    if find_section_by_name(db, dto.grade_level, &section_letter, academic_year.id).await?.is_some() {
+        return Err(SystemError::DuplicateError(format!("section {}{} of {}", dto.grade_level, section_letter, academic_year.name)));
+    }
+    check_homeroom_teacher(db, dto.homeroom_teacher_id).await?;
+
+    let new_section = section_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        grade_level: Set(dto.grade_level),
+        section_letter: Set(section_letter),
+        academic_year_id: Set(academic_year.id),
+        homeroom_teacher_id: Set(dto.homeroom_teacher_id),
+        room: Set(dto.room),
+        created_at: Set(Utc::now()),
+    };
+    match create_section_repo(db, new_section).await {
+        Ok(section) => {
+            info!("section successfully created by {}: {:?}", actor.email, section);
+            Ok(create_response_dto(&section, &academic_year.name))
+        }
+        Err(e) => {
+            error!("Failed to create section: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn update_section_service(db: &DatabaseConnection, id: String, dto: SectionRequestDto, actor: &AuthenticatedUser) -> Result<SectionResponseDto, SystemError> {
+    let selected_section = find_section(db, &id).await?;
+
+    let section_letter = dto.section_letter.to_uppercase();
+    let academic_year = find_academic_year(db, dto.academic_year_id).await?;
+    let existing = find_section_by_name(db, dto.grade_level, &section_letter, academic_year.id).await?;
+    if existing.is_some_and(|section| section.id != selected_section.id) {
+        return Err(SystemError::DuplicateError(format!("section {}{} of {}", dto.grade_level, section_letter, academic_year.name)));
+    }
+    check_homeroom_teacher(db, dto.homeroom_teacher_id).await?;
+
+    let mut active_section: section_model::ActiveModel = selected_section.into();
+    active_section.grade_level = Set(dto.grade_level);
+    active_section.section_letter = Set(section_letter);
+    active_section.academic_year_id = Set(academic_year.id);
+    active_section.homeroom_teacher_id = Set(dto.homeroom_teacher_id);
+    active_section.room = Set(dto.room);
+
+    match update_section_repo(db, active_section).await {
+        Ok(section) => {
+            info!("section successfully updated by {}: {:?}", actor.email, section);
+            Ok(create_response_dto(&section, &academic_year.name))
+        }
+        Err(e) => {
+            error!("Failed to update section: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+// the students stay, without a section
+pub async fn delete_section_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
+    let selected_section = find_section(db, &id).await?;
+    match delete_section_repo(db, selected_section).await {
+        Ok(delete_section) => {
+            info!("section {} successfully deleted by {}", id, actor.email);
+            Ok(delete_section)
+        }
+        Err(e) => {
+            error!("Failed to delete section: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn get_all_sections_paginate_service(db: &DatabaseConnection, academic_year_id: Option<Uuid>, grade_level: Option<i32>, page: u64, size: u64) -> Result<PaginateSectionResponseDto, SystemError> {
+    let sections = all_sections_repo(db, academic_year_id, grade_level, page, size).await?;
+    let sections_count = all_sections_count_repo(db, academic_year_id, grade_level).await?;
+
+    Ok(PaginateSectionResponseDto {
+        count: sections_count,
+        list: sections.iter()
+            .map(|(section, academic_year)| create_response_dto(section, academic_year.as_ref().map_or("", |academic_year| &academic_year.name)))
+            .collect(),
+    })
+}
+
+// the section's students in the active term unless another is asked for
+pub async fn get_section_students_service(db: &DatabaseConnection, id: String, term_id: Option<Uuid>) -> Result<Vec<SectionStudentDto>, SystemError> {
+    let selected_section = find_section(db, &id).await?;
+    let (term, _) = term_or_active(db, term_id).await?;
+
+    let placements = section_student_terms_repo(db, selected_section.id, term.id).await?;
+    let user_ids = placements.iter().filter_map(|(_, student)| student.as_ref().map(|student| student.user_id)).collect();
+    let users: HashMap<Uuid, _> = find_users_by_ids(db, user_ids).await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+
+    let mut students: Vec<SectionStudentDto> = placements.into_iter()
+        .filter_map(|(placement, student)| {
+            let student = student?;
+            let user = users.get(&student.user_id)?;
+            Some(SectionStudentDto {
+                student_id: student.id,
+                user_id: user.id,
+                name: user.name.clone(),
+                email: user.email.clone(),
+                class_teacher_id: placement.class_teacher_id,
+            })
+        })
+        .collect();
+    students.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(students)
+}
+
+// all students are moved or none, an unknown id fails the whole move,
+// a homeroom teacher becomes the class teacher of the moved students
+pub async fn move_students_service(db: &DatabaseConnection, id: String, dto: MoveStudentsRequestDto, actor: &AuthenticatedUser) -> Result<Vec<StudentResponseDto>, SystemError> {
+    let selected_section = find_section(db, &id).await?;
+
+    let txn = db.begin().await?;
+    let (term, academic_year) = term_or_active(&txn, dto.term_id).await?;
+    check_academic_year(&selected_section, &academic_year)?;
+    let students = find_students_by_ids(&txn, dto.student_ids.clone()).await?;
+    if let Some(missing) = dto.student_ids.iter().find(|id| !students.iter().any(|student| student.id == **id)) {
+        return Err(SystemError::NotFoundError(missing.to_string() + " student"));
+    }
+    for student in &students {
+        // a term without a placement yet starts from the student's current grade and class teacher
+        let (grade, class_teacher_id, from_section_id) = match find_student_term(&txn, student.id, term.id).await? {
+            Some(placement) => (placement.grade, placement.class_teacher_id, placement.section_id),
+            None => (student.grade.clone(), student.class_teacher_id, None),
+        };
+        save_student_term_repo(&txn, student_term_model::ActiveModel {
+            student_id: Set(student.id),
+            term_id: Set(term.id),
+            grade: Set(grade),
+            class_teacher_id: Set(selected_section.homeroom_teacher_id.or(class_teacher_id)),
+            section_id: Set(Some(selected_section.id)),
+            updated_at: Set(Utc::now()),
+        }).await?;
+        if from_section_id != Some(selected_section.id) {
+            info!("student {} moved from section {:?} to {} in term {} by {}", student.id, from_section_id, selected_section.id, term.name, actor.email);
+        }
+    }
+    if term.is_active {
+        move_students_repo(&txn, dto.student_ids.clone(), &selected_section).await?;
+    }
+    let moved = find_students_by_ids(&txn, dto.student_ids).await?;
+    txn.commit().await?;
+
+    Ok(moved.iter().map(create_student_response_dto).collect())
+}
+
+pub async fn remove_student_from_section_service(db: &DatabaseConnection, id: String, student_id: String, term_id: Option<Uuid>, actor: &AuthenticatedUser) -> Result<(), SystemError> {
+    let selected_section = find_section(db, &id).await?;
+    let student_id = parse_id(&student_id)?;
+
+    let txn = db.begin().await?;
+    let (term, _) = term_or_active(&txn, term_id).await?;
+    let placement = match find_student_term(&txn, student_id, term.id).await? {
+        Some(placement) if placement.section_id == Some(selected_section.id) => placement,
+        _ => return Err(SystemError::NotFoundError(format!("student {} in section {}", student_id, selected_section.id))),
+    };
+    let mut active_placement: student_term_model::ActiveModel = placement.into();
+    active_placement.section_id = Set(None);
+    active_placement.updated_at = Set(Utc::now());
+    save_student_term_repo(&txn, active_placement).await?;
+
+    if term.is_active {
+        if let Some(student) = Student::find_by_id(student_id).one(&txn).await? {
+            if student.section_id == Some(selected_section.id) {
+                remove_student_from_section_repo(&txn, student).await?;
+            }
+        }
+    }
+    txn.commit().await?;
+
+    info!("student {} removed from section {} in term {} by {}", student_id, selected_section.id, term.name, actor.email);
+    Ok(())
+}
+
+fn parse_id(id: &str) -> Result<Uuid, SystemError> {
+    match Uuid::parse_str(id) {
+        Ok(uuid) => Ok(uuid),
+        Err(_) => Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    }
+}
+
+async fn find_section(db: &DatabaseConnection, id: &str) -> Result<Model, SystemError> {
+    let section_id = parse_id(id)?;
+    let selected_section = Section::find_by_id(section_id).one(db).await?;
+    if selected_section.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(selected_section.unwrap())
+}
+
+async fn find_academic_year(db: &DatabaseConnection, academic_year_id: Uuid) -> Result<academic_year_model::Model, SystemError> {
+    match AcademicYear::find_by_id(academic_year_id).one(db).await? {
+        Some(academic_year) => Ok(academic_year),
+        None => Err(SystemError::NotFoundError(academic_year_id.to_string() + " academic year")),
+    }
+}
+
+async fn check_homeroom_teacher(db: &DatabaseConnection, homeroom_teacher_id: Option<Uuid>) -> Result<(), SystemError> {
+    if let Some(teacher_id) = homeroom_teacher_id {
+        if Teacher::find_by_id(teacher_id).one(db).await?.is_none() {
+            return Err(SystemError::NotFoundError(teacher_id.to_string() + " teacher"));
+        }
+    }
+    Ok(())
+}
+
+// a section only exists in its own academic year
+fn check_academic_year(section: &Model, academic_year: &academic_year_model::Model) -> Result<(), SystemError> {
+    if section.academic_year_id != academic_year.id {
+        return Err(SystemError::ValidationError(format!("section {}{} does not belong to {}, the academic year of the term", section.grade_level, section.section_letter, academic_year.name)));
+    }
+    Ok(())
+}
+
+fn create_response_dto(section: &Model, academic_year: &str) -> SectionResponseDto {
+    SectionResponseDto {
+        id: section.id,
+        name: format!("{}{}", section.grade_level, section.section_letter),
+        grade_level: section.grade_level,
+        section_letter: section.section_letter.clone(),
+        academic_year_id: section.academic_year_id,
+        academic_year: academic_year.to_string(),
+        homeroom_teacher_id: section.homeroom_teacher_id,
+        room: section.room.clone(),
+        created_at: section.created_at,
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, DeleteResult, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::permissions_granted_by;
+use crate::models::{api_key_model, api_key_permission_model, service_account_model};
+use crate::models::api_key_model::{ApiKeyRequestDto, ApiKeyResponseDto, CreatedApiKeyResponseDto};
+use crate::models::service_account_model::ServiceAccountRequestDto;
+use crate::repo::role_repo::find_permission_by_name;
+use crate::repo::service_account_repo::{all_service_accounts_repo, api_key_permissions_repo, create_api_key_permissions_repo, create_api_key_repo, create_service_account_repo, delete_service_account_repo, find_api_key_by_hash, find_api_key_by_id, find_service_account_by_id, find_service_account_by_name, service_account_api_keys_repo, touch_api_key_repo, update_api_key_repo};
+use crate::utill::secure_token::{generate_token, hash_token};
+
+// keys start with this so they are told apart from JWTs and easy to find in leaked code
+pub const API_KEY_PREFIX: &str = "stk_";
+
+pub async fn create_service_account_service(db: &DatabaseConnection, dto: ServiceAccountRequestDto, actor: &AuthenticatedUser) -> Result<service_account_model::Model, SystemError> {
+    if find_service_account_by_name(db, &dto.name).await?.is_some() {
+        return Err(SystemError::DuplicateError(dto.name + " service account"));
+    }
+
+    let new_account = service_account_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(dto.name),
+        description: Set(dto.description),
+        created_by: Set(actor.email.clone()),
+        created_at: Set(Utc::now()),
+    };
+
+    match create_service_account_repo(db, new_account).await {
+        Ok(account) => {
+            info!("service account {} successfully created by {}", account.name, actor.email);
+            Ok(account)
+        }
+        Err(e) => {
+            error!("Failed to create service account: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn get_all_service_accounts_service(db: &DatabaseConnection) -> Result<Vec<service_account_model::Model>, SystemError> {
+    all_service_accounts_repo(db).await
+}
+
+// removes the account together with all of its keys
+pub async fn delete_service_account_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
+    let account = find_service_account(db, &id).await?;
+    let name = account.name.clone();
+
+    match delete_service_account_repo(db, account).await {
+        Ok(deleted) => {
+            warn!("service account {} deleted by {}", name, actor.email);
+            Ok(deleted)
+        }
+        Err(e) => {
+            error!("Failed to delete service account: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn create_api_key_service(db: &DatabaseConnection, id: String, dto: ApiKeyRequestDto, actor: &AuthenticatedUser) -> Result<CreatedApiKeyResponseDto, SystemError> {
+    let account = find_service_account(db, &id).await?;
+
+    for permission in &dto.permissions {
+        if find_permission_by_name(db, permission).await?.is_none() {
+            return Err(SystemError::NotFoundError(permission.to_string() + " permission"));
+        }
+        // a key can't carry more than its creator holds
+        for covered in permissions_granted_by(permission) {
+            if !actor.holds_permission(db, &covered).await {
+                warn!("{} tried to create an api key with {} without holding {}", actor.email, permission, covered.name());
+                return Err(SystemError::ForbiddenError(format!("you don't hold the {} permission", covered.name())));
+            }
+        }
+    }
+
+    let api_key = format!("{}{}", API_KEY_PREFIX, generate_token());
+    let now = Utc::now();
+    let new_key = api_key_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        service_account_id: Set(account.id),
+        name: Set(dto.name),
+        prefix: Set(api_key[..API_KEY_PREFIX.len() + 8].to_string()),
+        key_hash: Set(hash_token(&api_key)),
+        expires_at: Set(now + Duration::days(dto.expires_in_days)),
+        created_at: Set(now),
+        last_used_at: Set(None),
+        revoked_at: Set(None),
+    };
+    let key = create_api_key_repo(db, new_key).await?;
+
+    let mut permissions = dto.permissions;
+    permissions.sort();
+    permissions.dedup();
+    let key_permissions = permissions
+        .iter()
+        .map(|permission| api_key_permission_model::ActiveModel {
+            api_key_id: Set(key.id),
+            permission_name: Set(permission.clone()),
+        })
+        .collect();
+    create_api_key_permissions_repo(db, key_permissions).await?;
+
+    info!("api key {} ({}) created for service account {} by {}", key.name, key.prefix, account.name, actor.email);
+    Ok(CreatedApiKeyResponseDto {
+        api_key,
+        key: create_response_dto(key, permissions),
+    })
+}
+
+pub async fn get_api_keys_service(db: &DatabaseConnection, id: String) -> Result<Vec<ApiKeyResponseDto>, SystemError> {
+    let account = find_service_account(db, &id).await?;
+
+    let mut keys = Vec::new();
+    for key in service_account_api_keys_repo(db, account.id).await? {
+        let permissions = api_key_permissions_repo(db, key.id).await?;
+        keys.push(create_response_dto(key, permissions));
+    }
+    Ok(keys)
+}
+
+pub async fn revoke_api_key_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<ApiKeyResponseDto, SystemError> {
+    let key_id = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let key = find_api_key_by_id(db, key_id).await?;
+    if key.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+
+    let mut active_key: api_key_model::ActiveModel = key.unwrap().into();
+    active_key.revoked_at = Set(Some(Utc::now()));
+    let key = update_api_key_repo(db, active_key).await?;
+
+    warn!("api key {} ({}) revoked by {}", key.name, key.prefix, actor.email);
+    let permissions = api_key_permissions_repo(db, key.id).await?;
+    Ok(create_response_dto(key, permissions))
+}
+
+// JwtMiddleware calls this for requests that carry an API key instead of a JWT
+pub async fn authenticate_api_key(db: &DatabaseConnection, api_key: &str) -> Result<Option<AuthenticatedUser>, SystemError> {
+    let key = match find_api_key_by_hash(db, &hash_token(api_key)).await? {
+        Some(key) => key,
+        None => return Ok(None),
+    };
+
+    let now = Utc::now();
+    if key.revoked_at.is_some() || key.expires_at <= now {
+        warn!("revoked or expired api key rejected: {}", key.prefix);
+        return Ok(None);
+    }
+    let account = match find_service_account_by_id(db, key.service_account_id).await? {
+        Some(account) => account,
+        None => return Ok(None),
+    };
+
+    // one write a minute is enough to see whether a key is still in use
+    if key.last_used_at.is_none_or(|last_used_at| now - last_used_at > Duration::minutes(1)) {
+        touch_api_key_repo(db, key.id).await?;
+    }
+
+    let permissions = api_key_permissions_repo(db, key.id).await?;
+    Ok(Some(AuthenticatedUser::from_api_key(&account, &key, permissions)))
+}
+
+
+async fn find_service_account(db: &DatabaseConnection, id: &str) -> Result<service_account_model::Model, SystemError> {
+    let account_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let account = find_service_account_by_id(db, account_id).await?;
+    if account.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(account.unwrap())
+}
+
+fn create_response_dto(key: api_key_model::Model, permissions: Vec<String>) -> ApiKeyResponseDto {
+    ApiKeyResponseDto {
+        id: key.id,
+        name: key.name,
+        prefix: key.prefix,
+        permissions,
+        expires_at: key.expires_at,
+        created_at: key.created_at,
+        last_used_at: key.last_used_at,
+        revoked_at: key.revoked_at,
+    }
+}
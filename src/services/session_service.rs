@@ -0,0 +1,132 @@
+use chrono::Utc;
+use log::{info, warn};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::{session_model, user_model, User};
+use crate::models::refresh_token_model::TokenResponseDto;
+use crate::models::session_model::SessionResponseDto;
+use crate::repo::refresh_token_repo::revoke_token_family_repo;
+use crate::repo::session_repo::{create_session_repo, extend_session_repo, find_session_by_id, revoke_session_repo, touch_session_repo, user_active_sessions_repo};
+use crate::services::token_service::issue_token_pair;
+use crate::utill::jwt::refresh_token_ttl;
+
+// every login starts a new session and with it a new refresh token family
+pub async fn start_session_service(db: &DatabaseConnection, user: &user_model::Model, client: ClientInfo) -> Result<TokenResponseDto, SystemError> {
+    let session = create_session(db, user, Uuid::new_v4(), client).await?;
+    issue_token_pair(db, user, session.id).await
+}
+
+// a refresh keeps the session alive, families from before sessions were tracked get one now
+pub async fn refresh_session_service(db: &DatabaseConnection, user: &user_model::Model, session_id: Uuid, client: ClientInfo) -> Result<(), SystemError> {
+    if find_session_by_id(db, session_id).await?.is_some() {
+        extend_session_repo(db, session_id, Utc::now() + refresh_token_ttl()).await?;
+    } else {
+        create_session(db, user, session_id, client).await?;
+    }
+    Ok(())
+}
+
+pub async fn record_session_use_service(db: &DatabaseConnection, session_id: Uuid) -> Result<(), SystemError> {
+    touch_session_repo(db, session_id).await?;
+    Ok(())
+}
+
+pub async fn get_my_sessions_service(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<Vec<SessionResponseDto>, SystemError> {
+    let sessions = user_active_sessions_repo(db, actor.user_id).await?;
+    Ok(sessions.into_iter().map(|session| create_response_dto(session, actor.session_id)).collect())
+}
+
+pub async fn revoke_my_session_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<(), SystemError> {
+    let session = find_user_session(db, actor.user_id, &id).await?;
+    revoke_session(db, session, actor).await
+}
+
+pub async fn get_user_sessions_service(db: &DatabaseConnection, id: String) -> Result<Vec<SessionResponseDto>, SystemError> {
+    let user = find_user(db, &id).await?;
+    let sessions = user_active_sessions_repo(db, user.id).await?;
+    Ok(sessions.into_iter().map(|session| create_response_dto(session, None)).collect())
+}
+
+pub async fn revoke_user_session_service(db: &DatabaseConnection, id: String, session_id: String, actor: &AuthenticatedUser) -> Result<(), SystemError> {
+    let user = find_user(db, &id).await?;
+    let session = find_user_session(db, user.id, &session_id).await?;
+    revoke_session(db, session, actor).await
+}
+
+// e.g. after a password change, the session that made the change stays signed in
+pub async fn revoke_other_sessions_service(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<usize, SystemError> {
+    let sessions = user_active_sessions_repo(db, actor.user_id).await?;
+    let mut revoked = 0;
+    for session in sessions.into_iter().filter(|session| Some(session.id) != actor.session_id) {
+        revoke_session(db, session, actor).await?;
+        revoked += 1;
+    }
+    Ok(revoked)
+}
+
+
+async fn create_session(db: &DatabaseConnection, user: &user_model::Model, id: Uuid, client: ClientInfo) -> Result<session_model::Model, SystemError> {
+    let now = Utc::now();
+    let new_session = session_model::ActiveModel {
+        id: Set(id),
+        user_id: Set(user.id),
+        user_agent: Set(client.user_agent),
+        ip_address: Set(client.ip_address),
+        created_at: Set(now),
+        last_used_at: Set(now),
+        expires_at: Set(now + refresh_token_ttl()),
+        revoked_at: Set(None),
+    };
+    let session = create_session_repo(db, new_session).await?;
+    info!("session {} started for user: {} from {:?}", session.id, user.email, session.ip_address);
+    Ok(session)
+}
+
+// ends the refresh token family, access tokens of the session are rejected from now on
+async fn revoke_session(db: &DatabaseConnection, session: session_model::Model, actor: &AuthenticatedUser) -> Result<(), SystemError> {
+    revoke_session_repo(db, session.id).await?;
+    revoke_token_family_repo(db, session.id).await?;
+    warn!("session {} of user {} revoked by {}", session.id, session.user_id, actor.email);
+    Ok(())
+}
+
+async fn find_user(db: &DatabaseConnection, id: &str) -> Result<user_model::Model, SystemError> {
+    let user_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(selected_user.unwrap())
+}
+
+// another user's session is reported as missing, not as forbidden
+async fn find_user_session(db: &DatabaseConnection, user_id: Uuid, id: &str) -> Result<session_model::Model, SystemError> {
+    let session_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    match find_session_by_id(db, session_id).await? {
+        Some(session) if session.user_id == user_id && session.revoked_at.is_none() => Ok(session),
+        _ => Err(SystemError::NotFoundError(id.to_string() + " session")),
+    }
+}
+
+fn create_response_dto(session: session_model::Model, current_session: Option<Uuid>) -> SessionResponseDto {
+    SessionResponseDto {
+        current: current_session == Some(session.id),
+        id: session.id,
+        user_agent: session.user_agent,
+        ip_address: session.ip_address,
+        created_at: session.created_at,
+        last_used_at: session.last_used_at,
+        expires_at: session.expires_at,
+    }
+}
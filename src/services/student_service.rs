@@ -1,17 +1,21 @@
-use actix_web::web::Data;
+use std::collections::HashMap;
+use chrono::Utc;
 use log::{error, info};
-use sea_orm::{ActiveModelTrait, DatabaseConnection, DeleteResult, EntityTrait, NotSet, Set};
-use uuid::{uuid, Uuid};
-use crate::exceptions::errors::{PasswordError, SystemError};
-use crate::models::{student_model, user_model, Student, User};
+use sea_orm::{DatabaseConnection, DeleteResult, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::models::{student_model, student_term_model, user_model, Section, Student, Teacher};
+use crate::models::student_term_model::StudentTermResponseDto;
+use crate::models::user_model::UserRole;
 use crate::models::student_model::{Model, PaginateStudentResponseDto, StudentRequestDto, StudentResponseDto};
 use crate::repo::student_repo::{all_students_count_repo, all_students_repo, create_student_repo, delete_student_repo, find_student_by_user, update_student_repo};
-use crate::repo::user_repo::{all_users_count_repo, all_users_repo, find_user_by_email, update_user_repo};
-use crate::utill::bcrypt::hash_password;
-use futures::future;
-use crate::models::user_model::{PaginateUserResponseDto, UserResponseDto};
+use crate::repo::academic_year_repo::all_academic_years_repo;
+use crate::repo::student_term_repo::{save_student_term_repo, student_terms_repo};
+use crate::repo::term_repo::find_active_term;
+use crate::repo::user_repo::{find_user_by_email, update_user_repo};
 
-pub async fn create_student_service(db: &DatabaseConnection, dto: StudentRequestDto) -> Result<Model, SystemError> {
+pub async fn create_student_service(db: &DatabaseConnection, dto: StudentRequestDto, actor: &AuthenticatedUser) -> Result<Model, SystemError> {
     let selected_user = find_user_by_email(db, &dto.user_email).await?;
 
     if selected_user.is_none() {
@@ -23,16 +27,22 @@ pub async fn create_student_service(db: &DatabaseConnection, dto: StudentRequest
         return Err(SystemError::DuplicateError(user.name + " student"));
     }
 
+    check_class_teacher(db, dto.class_teacher_id).await?;
+    check_section(db, dto.section_id).await?;
+
     let new_student = student_model::ActiveModel {
         id: Set(Uuid::new_v4()),
         grade: Set(dto.grade),
-        class_teacher: Set(dto.class_teacher),
+        class_teacher_id: Set(dto.class_teacher_id),
+        class_teacher: Set(None),
+        section_id: Set(dto.section_id),
         user_id: Set(user.id),
     };
     match create_student_repo(db, new_student).await {
         Ok(st) => {
-            info!("student successfully created: {:?}", st);
+            info!("student successfully created by {}: {:?}", actor.email, st);
             update_role(db, user).await?;
+            save_placement(db, &st).await?;
             Ok(st)
         }
         Err(e) => {
@@ -42,7 +52,7 @@ pub async fn create_student_service(db: &DatabaseConnection, dto: StudentRequest
     }
 }
 
-pub async fn update_student_service(db: &DatabaseConnection, id: String, dto: StudentRequestDto) -> Result<Model, SystemError> {
+pub async fn update_student_service(db: &DatabaseConnection, id: String, dto: StudentRequestDto, actor: &AuthenticatedUser) -> Result<Model, SystemError> {
 
     //convert String to uuid
     let st_id = match Uuid::parse_str(&id) {
@@ -57,15 +67,24 @@ pub async fn update_student_service(db: &DatabaseConnection, id: String, dto: St
     }
 
 
-    let mut select_student = select_student.unwrap();
+    check_class_teacher(db, dto.class_teacher_id).await?;
+    check_section(db, dto.section_id).await?;
+
+    let select_student = select_student.unwrap();
     let mut active_student: student_model::ActiveModel = select_student.into();
 
     active_student.grade = Set(dto.grade);
-    active_student.class_teacher = Set(dto.class_teacher);
+    active_student.class_teacher_id = Set(dto.class_teacher_id);
+    // a teacher assigned by hand resolves the legacy name
+    if dto.class_teacher_id.is_some() {
+        active_student.class_teacher = Set(None);
+    }
+    active_student.section_id = Set(dto.section_id);
 
     match update_student_repo(db, active_student).await {
         Ok(update_st) => {
-            info!("student successfully updated: {:?}", update_st);
+            info!("student successfully updated by {}: {:?}", actor.email, update_st);
+            save_placement(db, &update_st).await?;
             Ok(update_st)
         }
         Err(e) => {
@@ -75,7 +94,7 @@ pub async fn update_student_service(db: &DatabaseConnection, id: String, dto: St
     }
 }
 
-pub async fn delete_student_service(db: &DatabaseConnection, id: String) -> Result<DeleteResult, SystemError> {
+pub async fn delete_student_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
     let st_id = match Uuid::parse_str(&id) {
         Ok(uuid) => uuid,
         Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string()))
@@ -88,7 +107,7 @@ pub async fn delete_student_service(db: &DatabaseConnection, id: String) -> Resu
     let selected_student = selected_student.unwrap();
     match delete_student_repo(db, selected_student).await {
         Ok(delete_student) => {
-            info!("student successfully deleted");
+            info!("student {} successfully deleted by {}", id, actor.email);
             Ok(delete_student)
         }
         Err(e) => {
@@ -119,19 +138,84 @@ pub async fn get_all_students_paginate_service(
 }
 
 
-fn create_response_dto(student: &Model) -> StudentResponseDto {
+// where the student was in each term, newest first
+pub async fn get_student_terms_service(db: &DatabaseConnection, id: String) -> Result<Vec<StudentTermResponseDto>, SystemError> {
+    let st_id = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string()))
+    };
+    if Student::find_by_id(st_id).one(db).await?.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+
+    let academic_years: HashMap<Uuid, String> = all_academic_years_repo(db).await?
+        .into_iter()
+        .map(|academic_year| (academic_year.id, academic_year.name))
+        .collect();
+    let placements = student_terms_repo(db, st_id).await?;
+    Ok(placements.into_iter()
+        .filter_map(|(placement, term)| term.map(|term| StudentTermResponseDto {
+            term_id: term.id,
+            term: term.name,
+            academic_year: academic_years.get(&term.academic_year_id).cloned().unwrap_or_default(),
+            is_active: term.is_active,
+            grade: placement.grade,
+            class_teacher_id: placement.class_teacher_id,
+            section_id: placement.section_id,
+            updated_at: placement.updated_at,
+        }))
+        .collect())
+}
+
+
+pub fn create_response_dto(student: &Model) -> StudentResponseDto {
     StudentResponseDto {
         id: student.id,
         grade: student.grade.clone(),
-        class_teacher: student.class_teacher.clone(),
-        user_id: student.user_id.clone(),
+        class_teacher_id: student.class_teacher_id,
+        legacy_class_teacher: student.class_teacher.clone(),
+        section_id: student.section_id,
+        user_id: student.user_id,
+    }
+}
+
+
+async fn check_class_teacher(db: &DatabaseConnection, class_teacher_id: Option<Uuid>) -> Result<(), SystemError> {
+    if let Some(teacher_id) = class_teacher_id {
+        if Teacher::find_by_id(teacher_id).one(db).await?.is_none() {
+            return Err(SystemError::NotFoundError(teacher_id.to_string() + " teacher"));
+        }
     }
+    Ok(())
 }
 
+async fn check_section(db: &DatabaseConnection, section_id: Option<Uuid>) -> Result<(), SystemError> {
+    if let Some(section_id) = section_id {
+        if Section::find_by_id(section_id).one(db).await?.is_none() {
+            return Err(SystemError::NotFoundError(section_id.to_string() + " section"));
+        }
+    }
+    Ok(())
+}
+
+// the students row is the active term's placement, the term keeps its own copy
+async fn save_placement(db: &DatabaseConnection, student: &Model) -> Result<(), SystemError> {
+    if let Some(term) = find_active_term(db).await? {
+        save_student_term_repo(db, student_term_model::ActiveModel {
+            student_id: Set(student.id),
+            term_id: Set(term.id),
+            grade: Set(student.grade.clone()),
+            class_teacher_id: Set(student.class_teacher_id),
+            section_id: Set(student.section_id),
+            updated_at: Set(Utc::now()),
+        }).await?;
+    }
+    Ok(())
+}
 
 async fn update_role(db: &DatabaseConnection, user: user_model::Model) -> Result<(), SystemError> {
     let mut active_user: user_model::ActiveModel = user.into();
-    active_user.role = Set("Student".to_string());
+    active_user.role = Set(UserRole::Student);
 
     match update_user_repo(db, active_user).await {
         Ok(_) => {
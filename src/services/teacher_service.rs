@@ -0,0 +1,138 @@
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{DatabaseConnection, DeleteResult, EntityTrait, Set, TransactionTrait};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::{Action, Permission, Resource, Role};
+use crate::models::{teacher_model, user_model, user_role_model, Teacher};
+use crate::models::student_model::StudentResponseDto;
+use crate::models::teacher_model::{LegacyClassTeachersResponseDto, Model, PaginateTeacherResponseDto, TeacherRequestDto, TeacherResponseDto};
+use crate::repo::teacher_repo::{all_teachers_count_repo, all_teachers_repo, create_teacher_repo, delete_teacher_repo, find_teacher_by_user_id, link_legacy_class_teachers_repo, teacher_students_repo, unlinked_legacy_class_teachers_count_repo};
+use crate::repo::user_repo::find_user_by_email;
+use crate::repo::user_role_repo::{create_user_role_repo, delete_user_role_repo, find_user_role};
+use crate::services::student_service::create_response_dto as create_student_response_dto;
+use crate::services::user_role_service::user_role_names;
+
+// the user gets the Teacher role
+pub async fn create_teacher_service(db: &DatabaseConnection, dto: TeacherRequestDto, actor: &AuthenticatedUser) -> Result<TeacherResponseDto, SystemError> {
+    let selected_user = find_user_by_email(db, &dto.user_email).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(dto.user_email + " user"));
+    }
+    let user = selected_user.unwrap();
+    if find_teacher_by_user_id(db, user.id).await?.is_some() {
+        return Err(SystemError::DuplicateError(user.name + " teacher"));
+    }
+
+    let new_teacher = teacher_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        department: Set(dto.department),
+        created_at: Set(Utc::now()),
+    };
+    let teacher = match create_teacher_repo(db, new_teacher).await {
+        Ok(teacher) => teacher,
+        Err(e) => {
+            error!("Failed to create teacher: {:?}", e);
+            return Err(e);
+        }
+    };
+    info!("teacher successfully created by {}: {:?}", actor.email, teacher);
+
+    grant_teacher_role(db, &user).await?;
+    Ok(create_response_dto(&teacher, &user))
+}
+
+pub async fn delete_teacher_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
+    let teacher = find_teacher(db, &id).await?;
+    let user_id = teacher.user_id;
+
+    let deleted = match delete_teacher_repo(db, teacher).await {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            error!("Failed to delete teacher: {:?}", e);
+            return Err(e);
+        }
+    };
+    if let Some(user_role) = find_user_role(db, user_id, Role::Teacher.as_str()).await? {
+        delete_user_role_repo(db, user_role).await?;
+    }
+    info!("teacher {} successfully deleted by {}", id, actor.email);
+    Ok(deleted)
+}
+
+pub async fn get_all_teachers_paginate_service(db: &DatabaseConnection, search_text: String, page: u64, size: u64) -> Result<PaginateTeacherResponseDto, SystemError> {
+    let teachers = all_teachers_repo(db, &search_text, page, size).await?;
+    let teachers_count = all_teachers_count_repo(db, &search_text).await?;
+
+    Ok(PaginateTeacherResponseDto {
+        count: teachers_count,
+        list: teachers.iter()
+            .filter_map(|(teacher, user)| user.as_ref().map(|user| create_response_dto(teacher, user)))
+            .collect(),
+    })
+}
+
+// teachers see their own students, anyone allowed to read students sees those of every teacher
+pub async fn get_teacher_students_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<Vec<StudentResponseDto>, SystemError> {
+    let teacher = find_teacher(db, &id).await?;
+    if teacher.user_id != actor.user_id && !actor.holds_permission(db, &Permission::new(Resource::Students, Action::Read)).await {
+        return Err(SystemError::ForbiddenError("listing another teacher's students needs students:read".to_string()));
+    }
+
+    let students = teacher_students_repo(db, teacher.id).await?;
+    Ok(students.iter().map(|(student, _)| create_student_response_dto(student)).collect())
+}
+
+// students from before teacher records whose old class teacher name now matches a teacher,
+// run once the teachers are created, names that match no teacher or several stay for an admin
+pub async fn link_legacy_class_teachers_service(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<LegacyClassTeachersResponseDto, SystemError> {
+    let txn = db.begin().await?;
+    let linked = link_legacy_class_teachers_repo(&txn).await?;
+    let unlinked = unlinked_legacy_class_teachers_count_repo(&txn).await?;
+    txn.commit().await?;
+
+    info!("{} students linked to their class teacher by {}, {} names left unlinked", linked, actor.email, unlinked);
+    Ok(LegacyClassTeachersResponseDto { linked, unlinked })
+}
+
+
+async fn find_teacher(db: &DatabaseConnection, id: &str) -> Result<Model, SystemError> {
+    let teacher_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_teacher = Teacher::find_by_id(teacher_id).one(db).await?;
+    if selected_teacher.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(selected_teacher.unwrap())
+}
+
+// an additional role, the user keeps the primary role, e.g. an admin who also teaches
+async fn grant_teacher_role(db: &DatabaseConnection, user: &user_model::Model) -> Result<(), SystemError> {
+    if user_role_names(db, user).await?.iter().any(|role| role == Role::Teacher.as_str()) {
+        return Ok(());
+    }
+    let new_user_role = user_role_model::ActiveModel {
+        user_id: Set(user.id),
+        role_name: Set(Role::Teacher.as_str().to_string()),
+        granted_at: Set(Utc::now()),
+    };
+    create_user_role_repo(db, new_user_role).await?;
+    info!("teacher role added to {}", user.email);
+    Ok(())
+}
+
+fn create_response_dto(teacher: &Model, user: &user_model::Model) -> TeacherResponseDto {
+    TeacherResponseDto {
+        id: teacher.id,
+        user_id: teacher.user_id,
+        name: user.name.clone(),
+        email: user.email.clone(),
+        department: teacher.department.clone(),
+        created_at: teacher.created_at,
+    }
+}
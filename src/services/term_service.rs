@@ -0,0 +1,198 @@
+use chrono::Utc;
+use log::{error, info};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait, Set, TransactionTrait};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::models::{academic_year_model, term_model, AcademicYear};
+use crate::models::term_model::{Model, TermRequestDto, TermResponseDto};
+use crate::repo::term_repo::{academic_year_terms_repo, apply_placements_repo, carry_over_placements_repo, create_term_repo, deactivate_terms_repo, delete_term_repo, find_active_term, find_term_with_academic_year, term_history_count_repo, update_term_repo};
+
+// the first term becomes the active one
+pub async fn create_term_service(db: &DatabaseConnection, dto: TermRequestDto, actor: &AuthenticatedUser) -> Result<TermResponseDto, SystemError> {
+    let academic_year = find_academic_year(db, dto.academic_year_id).await?;
+    check_dates(db, &academic_year, &dto, None).await?;
+
+    let txn = db.begin().await?;
+    let first_term = find_active_term(&txn).await?.is_none();
+    let new_term = term_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        academic_year_id: Set(academic_year.id),
+        name: Set(dto.name),
+        start_date: Set(dto.start_date),
+        end_date: Set(dto.end_date),
+        is_active: Set(false),
+        created_at: Set(Utc::now()),
+    };
+    let term = match create_term_repo(&txn, new_term).await {
+        Ok(term) => term,
+        Err(e) => {
+            error!("Failed to create term: {:?}", e);
+            return Err(e);
+        }
+    };
+    let term = if first_term { activate(&txn, term, &academic_year).await? } else { term };
+    txn.commit().await?;
+
+    info!("term successfully created by {}: {:?}", actor.email, term);
+    Ok(create_response_dto(&term))
+}
+
+pub async fn update_term_service(db: &DatabaseConnection, id: String, dto: TermRequestDto, actor: &AuthenticatedUser) -> Result<TermResponseDto, SystemError> {
+    let (selected_term, _) = find_term(db, &id).await?;
+    if dto.academic_year_id != selected_term.academic_year_id {
+        return Err(SystemError::ValidationError("a term can't move to another academic year".to_string()));
+    }
+    let academic_year = find_academic_year(db, dto.academic_year_id).await?;
+    check_dates(db, &academic_year, &dto, Some(selected_term.id)).await?;
+
+    let mut active_term: term_model::ActiveModel = selected_term.into();
+    active_term.name = Set(dto.name);
+    active_term.start_date = Set(dto.start_date);
+    active_term.end_date = Set(dto.end_date);
+
+    match update_term_repo(db, active_term).await {
+        Ok(term) => {
+            info!("term successfully updated by {}: {:?}", actor.email, term);
+            Ok(create_response_dto(&term))
+        }
+        Err(e) => {
+            error!("Failed to update term: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+// only a term nothing happened in can go, the history of past terms is kept
+pub async fn delete_term_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
+    let (selected_term, _) = find_term(db, &id).await?;
+    if selected_term.is_active {
+        return Err(SystemError::ValidationError("the active term can't be deleted".to_string()));
+    }
+    if term_history_count_repo(db, selected_term.id).await? > 0 {
+        return Err(SystemError::ValidationError(format!("term {} still has enrollments or placements", selected_term.name)));
+    }
+
+    match delete_term_repo(db, selected_term).await {
+        Ok(delete_term) => {
+            info!("term {} successfully deleted by {}", id, actor.email);
+            Ok(delete_term)
+        }
+        Err(e) => {
+            error!("Failed to delete term: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+// the current placements are carried into the new term unless it already has its own
+pub async fn activate_term_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<TermResponseDto, SystemError> {
+    let (selected_term, academic_year) = find_term(db, &id).await?;
+    if selected_term.is_active {
+        return Ok(create_response_dto(&selected_term));
+    }
+
+    let txn = db.begin().await?;
+    let term = activate(&txn, selected_term, &academic_year).await?;
+    txn.commit().await?;
+
+    info!("term {} of {} activated by {}", term.name, academic_year.name, actor.email);
+    Ok(create_response_dto(&term))
+}
+
+pub async fn get_active_term_service(db: &DatabaseConnection) -> Result<TermResponseDto, SystemError> {
+    let term = active_term(db).await?;
+    Ok(create_response_dto(&term))
+}
+
+pub async fn get_academic_year_terms_service(db: &DatabaseConnection, academic_year_id: String) -> Result<Vec<TermResponseDto>, SystemError> {
+    let academic_year_id = parse_id(&academic_year_id)?;
+    let academic_year = find_academic_year(db, academic_year_id).await?;
+
+    let terms = academic_year_terms_repo(db, academic_year.id).await?;
+    Ok(terms.iter().map(create_response_dto).collect())
+}
+
+pub async fn active_term<C: ConnectionTrait>(db: &C) -> Result<Model, SystemError> {
+    match find_active_term(db).await? {
+        Some(term) => Ok(term),
+        None => Err(SystemError::ValidationError("there is no active term".to_string())),
+    }
+}
+
+// the requested term, or the active one
+pub async fn term_or_active<C: ConnectionTrait>(db: &C, term_id: Option<Uuid>) -> Result<(Model, academic_year_model::Model), SystemError> {
+    let term_id = match term_id {
+        Some(term_id) => term_id,
+        None => active_term(db).await?.id,
+    };
+    match find_term_with_academic_year(db, term_id).await? {
+        Some((term, Some(academic_year))) => Ok((term, academic_year)),
+        _ => Err(SystemError::NotFoundError(term_id.to_string() + " term")),
+    }
+}
+
+
+fn parse_id(id: &str) -> Result<Uuid, SystemError> {
+    match Uuid::parse_str(id) {
+        Ok(uuid) => Ok(uuid),
+        Err(_) => Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    }
+}
+
+async fn find_term(db: &DatabaseConnection, id: &str) -> Result<(Model, academic_year_model::Model), SystemError> {
+    let term_id = parse_id(id)?;
+    match find_term_with_academic_year(db, term_id).await? {
+        Some((term, Some(academic_year))) => Ok((term, academic_year)),
+        _ => Err(SystemError::NotFoundError(id.to_string() + " id")),
+    }
+}
+
+async fn find_academic_year(db: &DatabaseConnection, academic_year_id: Uuid) -> Result<academic_year_model::Model, SystemError> {
+    let academic_year = AcademicYear::find_by_id(academic_year_id).one(db).await?;
+    if academic_year.is_none() {
+        return Err(SystemError::NotFoundError(academic_year_id.to_string() + " academic year"));
+    }
+    Ok(academic_year.unwrap())
+}
+
+// inside the academic year and clear of its other terms
+async fn check_dates(db: &DatabaseConnection, academic_year: &academic_year_model::Model, dto: &TermRequestDto, term_id: Option<Uuid>) -> Result<(), SystemError> {
+    if dto.start_date >= dto.end_date {
+        return Err(SystemError::ValidationError("a term has to start before it ends".to_string()));
+    }
+    if dto.start_date < academic_year.start_date || dto.end_date > academic_year.end_date {
+        return Err(SystemError::ValidationError(format!("the term has to fall within {} ({} to {})", academic_year.name, academic_year.start_date, academic_year.end_date)));
+    }
+    let overlapping = academic_year_terms_repo(db, academic_year.id).await?
+        .into_iter()
+        .find(|term| Some(term.id) != term_id && term.start_date <= dto.end_date && dto.start_date <= term.end_date);
+    if let Some(term) = overlapping {
+        return Err(SystemError::ValidationError(format!("the term overlaps {} ({} to {})", term.name, term.start_date, term.end_date)));
+    }
+    Ok(())
+}
+
+async fn activate<C: ConnectionTrait>(db: &C, term: Model, academic_year: &academic_year_model::Model) -> Result<Model, SystemError> {
+    deactivate_terms_repo(db).await?;
+    let mut active_term: term_model::ActiveModel = term.into();
+    active_term.is_active = Set(true);
+    let term = update_term_repo(db, active_term).await?;
+
+    let carried_over = carry_over_placements_repo(db, term.id, academic_year.id).await?;
+    apply_placements_repo(db, term.id).await?;
+    info!("{} student placements carried over into term {}", carried_over, term.name);
+    Ok(term)
+}
+
+fn create_response_dto(term: &Model) -> TermResponseDto {
+    TermResponseDto {
+        id: term.id,
+        academic_year_id: term.academic_year_id,
+        name: term.name.clone(),
+        start_date: term.start_date,
+        end_date: term.end_date,
+        is_active: term.is_active,
+        created_at: term.created_at,
+    }
+}
@@ -0,0 +1,235 @@
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use crate::exceptions::errors::{JwtError, SystemError};
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::models::{refresh_token_model, revoked_token_model, user_model, User};
+use crate::models::refresh_token_model::{LogoutRequestDto, RefreshTokenRequestDto, TokenResponseDto};
+use crate::models::user_model::{ImpersonateRequestDto, ImpersonationResponseDto};
+use crate::repo::refresh_token_repo::{create_refresh_token_repo, find_refresh_token_by_hash, mark_refresh_token_used_repo, revoke_token_family_repo, revoke_user_refresh_tokens_repo};
+use crate::repo::revoked_token_repo::{create_revoked_token_repo, delete_expired_revoked_tokens_repo, find_revoked_token_by_jti};
+use crate::repo::session_repo::{find_session_by_id, revoke_session_repo, revoke_user_sessions_repo};
+use crate::repo::user_repo::update_user_repo;
+use crate::services::session_service::refresh_session_service;
+use crate::services::user_role_service::{is_admin, user_role_names};
+use crate::utill::jwt::{access_token_ttl, create_token, impersonation_ttl, refresh_token_ttl, Claims, Impersonator};
+use crate::utill::secure_token::{generate_token, hash_token};
+
+// issue an access token and a refresh token belonging to the given family, the family is the session
+pub async fn issue_token_pair(db: &DatabaseConnection, user: &user_model::Model, family_id: Uuid) -> Result<TokenResponseDto, SystemError> {
+    let mut claims = Claims::new(user.id, user.email.clone(), user_role_names(db, user).await?);
+    claims.restricted = !user.email_verified;
+    claims.sid = Some(family_id);
+    let access_token = match create_token(&claims) {
+        Ok(token) => token,
+        Err(e) => {
+            error!("token not created {:?}", e);
+            return Err(SystemError::JwtError(JwtError::TokenError("Failed to generate token : ".to_string())));
+        }
+    };
+
+    let refresh_token = generate_token();
+    let now = Utc::now();
+    let new_refresh_token = refresh_token_model::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        family_id: Set(family_id),
+        token_hash: Set(hash_token(&refresh_token)),
+        expires_at: Set(now + refresh_token_ttl()),
+        created_at: Set(now),
+        used_at: Set(None),
+        revoked_at: Set(None),
+    };
+    create_refresh_token_repo(db, new_refresh_token).await?;
+
+    Ok(TokenResponseDto {
+        access_token,
+        refresh_token,
+        token_type: "Bearer".to_string(),
+        expires_in: access_token_ttl().num_seconds(),
+    })
+}
+
+// rotate a refresh token, replaying a used token revokes its whole family
+pub async fn refresh_token_service(db: &DatabaseConnection, dto: RefreshTokenRequestDto, client: ClientInfo) -> Result<TokenResponseDto, SystemError> {
+    let selected_token = find_refresh_token_by_hash(db, &hash_token(&dto.refresh_token)).await?;
+    if selected_token.is_none() {
+        return Err(SystemError::JwtError(JwtError::InvalidRefreshToken));
+    }
+    let selected_token = selected_token.unwrap();
+
+    if selected_token.used_at.is_some() || selected_token.revoked_at.is_some() {
+        return Err(revoke_family(db, &selected_token).await);
+    }
+
+    if selected_token.expires_at <= Utc::now() {
+        return Err(SystemError::JwtError(JwtError::ExpiredRefreshToken));
+    }
+
+    // a concurrent request may have rotated the same token in the meantime
+    if mark_refresh_token_used_repo(db, selected_token.id).await? == 0 {
+        return Err(revoke_family(db, &selected_token).await);
+    }
+
+    let user = User::find_by_id(selected_token.user_id).one(db).await?;
+    if user.is_none() {
+        return Err(SystemError::JwtError(JwtError::InvalidRefreshToken));
+    }
+    let user = user.unwrap();
+
+    let tokens = issue_token_pair(db, &user, selected_token.family_id).await?;
+    refresh_session_service(db, &user, selected_token.family_id, client).await?;
+    info!("refresh token rotated for user: {}", user.email);
+    Ok(tokens)
+}
+
+// a token is revoked when its jti is denylisted, its session was revoked or it was issued before the user's cutoff
+pub async fn is_token_revoked_service(db: &DatabaseConnection, claims: &Claims) -> Result<bool, SystemError> {
+    if find_revoked_token_by_jti(db, &claims.jti).await?.is_some() {
+        return Ok(true);
+    }
+    if let Some(session_id) = claims.sid {
+        if find_session_by_id(db, session_id).await?.is_some_and(|session| session.revoked_at.is_some()) {
+            return Ok(true);
+        }
+    }
+
+    // an impersonation token dies with the tokens of either user
+    if let Some(impersonator) = &claims.act {
+        if is_user_token_revoked(db, impersonator.uid, claims.iat).await? {
+            return Ok(true);
+        }
+    }
+    is_user_token_revoked(db, claims.uid, claims.iat).await
+}
+
+// issue a short lived token to act as another user, read-only unless asked otherwise
+pub async fn impersonate_user_service(db: &DatabaseConnection, id: String, dto: ImpersonateRequestDto, actor: &AuthenticatedUser) -> Result<ImpersonationResponseDto, SystemError> {
+    if actor.impersonator.is_some() {
+        return Err(SystemError::ValidationError("Impersonation tokens can't start another impersonation".to_string()));
+    }
+    let user_id = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    let selected_user = selected_user.unwrap();
+    if selected_user.id == actor.user_id || is_admin(db, &selected_user).await? {
+        return Err(SystemError::ValidationError("Administrators can't be impersonated".to_string()));
+    }
+
+    let read_only = dto.read_only.unwrap_or(true);
+    let mut claims = Claims::new(selected_user.id, selected_user.email.clone(), user_role_names(db, &selected_user).await?);
+    claims.exp = (Utc::now() + impersonation_ttl()).timestamp() as usize;
+    claims.restricted = !selected_user.email_verified;
+    claims.act = Some(Impersonator {
+        sub: actor.email.clone(),
+        uid: actor.user_id,
+    });
+    claims.read_only = read_only;
+
+    let access_token = match create_token(&claims) {
+        Ok(token) => token,
+        Err(e) => {
+            error!("token not created {:?}", e);
+            return Err(SystemError::JwtError(JwtError::TokenError("Failed to generate token : ".to_string())));
+        }
+    };
+
+    warn!("impersonation started: {} as {} (read-only: {}, token {}), reason: {}", actor.email, selected_user.email, read_only, claims.jti, dto.reason);
+    Ok(ImpersonationResponseDto {
+        access_token,
+        token_type: "Bearer".to_string(),
+        expires_in: impersonation_ttl().num_seconds(),
+        impersonated: selected_user.email,
+        read_only,
+    })
+}
+
+pub async fn logout_service(db: &DatabaseConnection, user: &AuthenticatedUser, dto: LogoutRequestDto) -> Result<(), SystemError> {
+    let expires_at = DateTime::from_timestamp(user.expires_at as i64, 0).unwrap_or_else(Utc::now);
+    let revoked_token = revoked_token_model::ActiveModel {
+        jti: Set(user.token_id.clone()),
+        subject: Set(user.email.clone()),
+        expires_at: Set(expires_at),
+        revoked_at: Set(Utc::now()),
+    };
+    create_revoked_token_repo(db, revoked_token).await?;
+
+    if let Some(session_id) = user.session_id {
+        revoke_session_repo(db, session_id).await?;
+        revoke_token_family_repo(db, session_id).await?;
+    }
+    if let Some(refresh_token) = dto.refresh_token {
+        if let Some(token) = find_refresh_token_by_hash(db, &hash_token(&refresh_token)).await? {
+            if token.user_id == user.user_id {
+                revoke_token_family_repo(db, token.family_id).await?;
+            }
+        }
+    }
+
+    // keep the denylist small, expired tokens fail verification anyway
+    delete_expired_revoked_tokens_repo(db).await?;
+
+    info!("user logged out: {}", user.email);
+    Ok(())
+}
+
+// revoke every access and refresh token of a user, e.g. when the account is compromised
+pub async fn revoke_all_user_tokens_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<user_model::Model, SystemError> {
+    let user_id = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    let selected_user = selected_user.unwrap();
+
+    let revoked_refresh_tokens = revoke_user_refresh_tokens_repo(db, selected_user.id).await?;
+    revoke_user_sessions_repo(db, selected_user.id).await?;
+
+    let mut active_user: user_model::ActiveModel = selected_user.into();
+    active_user.tokens_revoked_at = Set(Some(Utc::now()));
+
+    match update_user_repo(db, active_user).await {
+        Ok(user) => {
+            warn!("all tokens revoked for user: {} ({} refresh tokens) by {}", user.email, revoked_refresh_tokens, actor.email);
+            Ok(user)
+        }
+        Err(e) => {
+            error!("Failed to revoke user tokens: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+
+async fn is_user_token_revoked(db: &DatabaseConnection, user_id: Uuid, issued_at: usize) -> Result<bool, SystemError> {
+    let user = User::find_by_id(user_id).one(db).await?;
+    match user {
+        Some(user) => Ok(user
+            .tokens_revoked_at
+            .is_some_and(|revoked_at| issued_at as i64 <= revoked_at.timestamp())),
+        None => Ok(true),
+    }
+}
+
+async fn revoke_family(db: &DatabaseConnection, token: &refresh_token_model::Model) -> SystemError {
+    warn!("refresh token reuse detected, revoking token family {}", token.family_id);
+    match revoke_token_family_repo(db, token.family_id).await {
+        Ok(_) => SystemError::JwtError(JwtError::RefreshTokenReused),
+        Err(e) => {
+            error!("failed to revoke token family: {:?}", e);
+            e
+        }
+    }
+}
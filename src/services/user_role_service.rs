@@ -0,0 +1,132 @@
+use chrono::Utc;
+use log::{info, warn};
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, Set, TransactionTrait};
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::Role;
+use crate::models::{user_model, user_role_model, User};
+use crate::models::user_model::UserRole;
+use crate::models::user_role_model::{AddUserRoleRequestDto, UserRolesResponseDto};
+use crate::repo::role_repo::find_role_by_name;
+use crate::repo::user_repo::lock_admins_repo;
+use crate::repo::user_role_repo::{create_user_role_repo, delete_user_role_repo, find_user_role, lock_role_holders_repo, user_roles_repo};
+use crate::services::token_service::revoke_all_user_tokens_service;
+
+// every role of the user for the token, the primary role first
+pub async fn user_role_names<C: ConnectionTrait>(db: &C, user: &user_model::Model) -> Result<Vec<String>, SystemError> {
+    let mut roles = vec![user.role.as_str().to_string()];
+    for user_role in user_roles_repo(db, user.id).await? {
+        if !roles.contains(&user_role.role_name) {
+            roles.push(user_role.role_name);
+        }
+    }
+    Ok(roles)
+}
+
+pub async fn is_admin<C: ConnectionTrait>(db: &C, user: &user_model::Model) -> Result<bool, SystemError> {
+    Ok(user_role_names(db, user).await?.iter().any(|role| role == Role::Admin.as_str()))
+}
+
+// refuse when no admin other than the user would be left, the admin rows stay locked until the transaction ends
+pub async fn ensure_admin_remains<C: ConnectionTrait>(db: &C, user: &user_model::Model) -> Result<(), SystemError> {
+    let admins = lock_admins_repo(db).await?;
+    let admin_holders = lock_role_holders_repo(db, Role::Admin.as_str()).await?;
+    let other_admin = admins.iter().map(|admin| admin.id)
+        .chain(admin_holders.iter().map(|holder| holder.user_id))
+        .any(|id| id != user.id);
+    if !other_admin {
+        warn!("refused to remove the last admin: {}", user.email);
+        return Err(SystemError::LastAdminError);
+    }
+    Ok(())
+}
+
+pub async fn get_user_roles_service(db: &DatabaseConnection, id: String) -> Result<UserRolesResponseDto, SystemError> {
+    let selected_user = find_user(db, &id).await?;
+    create_response_dto(db, &selected_user).await
+}
+
+// the role has to exist, only admins hand out the admin role,
+// the user's tokens are revoked so the new role applies at once
+pub async fn add_user_role_service(db: &DatabaseConnection, id: String, dto: AddUserRoleRequestDto, actor: &AuthenticatedUser) -> Result<UserRolesResponseDto, SystemError> {
+    let selected_user = find_user(db, &id).await?;
+
+    let role = find_role_by_name(db, &dto.role).await?;
+    if role.is_none() {
+        return Err(SystemError::NotFoundError(dto.role + " role"));
+    }
+    let role = role.unwrap();
+
+    if user_role_names(db, &selected_user).await?.contains(&role.name) {
+        return Err(SystemError::DuplicateError(role.name + " role"));
+    }
+    if role.name == Role::Admin.as_str() && !actor.roles.contains(&Role::Admin) {
+        warn!("{} may not grant the admin role to {}", actor.email, selected_user.email);
+        return Err(SystemError::ValidationError("Only admins can grant or remove the admin role".to_string()));
+    }
+
+    let new_user_role = user_role_model::ActiveModel {
+        user_id: Set(selected_user.id),
+        role_name: Set(role.name.clone()),
+        granted_at: Set(Utc::now()),
+    };
+    create_user_role_repo(db, new_user_role).await?;
+    info!("role {} added to {} by {}", role.name, selected_user.email, actor.email);
+
+    let updated_user = revoke_all_user_tokens_service(db, id, actor).await?;
+    create_response_dto(db, &updated_user).await
+}
+
+// the primary role can only be changed, not removed
+pub async fn remove_user_role_service(db: &DatabaseConnection, id: String, role_name: String, actor: &AuthenticatedUser) -> Result<UserRolesResponseDto, SystemError> {
+    let selected_user = find_user(db, &id).await?;
+
+    if role_name == selected_user.role.as_str() {
+        return Err(SystemError::ValidationError(role_name + " is the primary role of the user, change it instead"));
+    }
+    let user_role = find_user_role(db, selected_user.id, &role_name).await?;
+    if user_role.is_none() {
+        return Err(SystemError::NotFoundError(role_name + " role"));
+    }
+    let user_role = user_role.unwrap();
+
+    let txn = db.begin().await?;
+    if role_name == Role::Admin.as_str() {
+        if !actor.roles.contains(&Role::Admin) {
+            warn!("{} may not remove the admin role of {}", actor.email, selected_user.email);
+            return Err(SystemError::ValidationError("Only admins can grant or remove the admin role".to_string()));
+        }
+        if selected_user.role != UserRole::Admin {
+            ensure_admin_remains(&txn, &selected_user).await?;
+        }
+    }
+    delete_user_role_repo(&txn, user_role).await?;
+    txn.commit().await?;
+    info!("role {} removed from {} by {}", role_name, selected_user.email, actor.email);
+
+    let updated_user = revoke_all_user_tokens_service(db, id, actor).await?;
+    create_response_dto(db, &updated_user).await
+}
+
+
+async fn find_user(db: &DatabaseConnection, id: &str) -> Result<user_model::Model, SystemError> {
+    let user_id = match Uuid::parse_str(id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string())),
+    };
+
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    Ok(selected_user.unwrap())
+}
+
+async fn create_response_dto(db: &DatabaseConnection, user: &user_model::Model) -> Result<UserRolesResponseDto, SystemError> {
+    Ok(UserRolesResponseDto {
+        user_id: user.id,
+        primary_role: user.role.as_str().to_string(),
+        roles: user_role_names(db, user).await?,
+    })
+}
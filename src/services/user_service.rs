@@ -1,39 +1,56 @@
-use crate::exceptions::errors::{JwtError, PasswordError, SystemError};
-use crate::models::user_model::{Entity, LoginRequestDto, Model, PaginateUserResponseDto, UserRequestDto, UserResponseDto};
+use crate::exceptions::errors::{PasswordError, SystemError};
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::client_info::ClientInfo;
+use crate::midleware::permission::Role;
+use crate::models::user_model::{ChangePasswordRequestDto, ChangeRoleRequestDto, LoginRequestDto, LoginResponseDto, Model, PaginateUserResponseDto, UpdateProfileRequestDto, UserRequestDto, UserResponseDto, UserRole};
 use crate::models::{user_model, User};
 use crate::repo::user_repo::{all_users_count_repo, all_users_repo, create_user_repo, delete_user_repo, find_user_by_email, update_user_repo};
-use crate::utill::bcrypt::{hash_password, verify_password};
-use crate::utill::jwt::create_token;
+use crate::repo::user_role_repo::find_user_role;
+use crate::utill::password_hash::{hash_password, needs_rehash, verify_password};
+use crate::utill::password_policy::validate_password;
+use crate::services::email_verification_service::{send_verification_mail_service, unverified_login_restricted};
+use crate::services::login_throttle_service::{check_login_allowed, record_login_failure, reset_login_failures};
+use crate::services::mfa_service::create_mfa_challenge;
+use crate::services::session_service::{revoke_other_sessions_service, start_session_service};
+use crate::services::token_service::revoke_all_user_tokens_service;
+use crate::services::user_role_service::{ensure_admin_remains, is_admin};
 use log::{error, info, warn};
-use sea_orm::{ActiveModelTrait, DatabaseConnection, DeleteResult, EntityTrait, NotSet, Set};
+use sea_orm::{DatabaseConnection, DeleteResult, EntityTrait, Set, TransactionTrait};
 use uuid::Uuid;
 
-pub async fn user_login_service(db: &DatabaseConnection, dto: LoginRequestDto) -> Result<Option<String>, SystemError> {
+pub async fn user_login_service(db: &DatabaseConnection, dto: LoginRequestDto, client: ClientInfo) -> Result<Option<LoginResponseDto>, SystemError> {
     let selected_user = find_user_by_email(db, &dto.username).await?;
-    let mut roles = Vec::new();
     if selected_user.is_none() {
         return Err(SystemError::NotFoundError(dto.username));
     }
     let selected_user = selected_user.unwrap();
-    roles.push(selected_user.role);
+
+    check_login_allowed(&selected_user)?;
 
     let is_verify = verify_password(&dto.password, &selected_user.password)
-        .unwrap_or_else(|e| false);
+        .unwrap_or(false);
 
     if is_verify {
-        let token = create_token(selected_user.email, roles);
-        match token {
-            Ok(token) => {
-                info!("token created successfully: {:?}",token);
-                Ok(Some(token))
-            }
-            Err(e) => {
-                error!("token not created {:?}",e);
-                Err(SystemError::JwtError(JwtError::TokenError("Failed to generate token : ".to_string())))
-            }
+        let selected_user = rehash_if_outdated(db, selected_user, &dto.password).await;
+
+        if !selected_user.email_verified && !unverified_login_restricted() {
+            warn!("login refused, email not verified: {}", selected_user.email);
+            return Err(SystemError::EmailNotVerified);
+        }
+
+        // failures are only reset after the second step, otherwise the password alone would allow guessing codes
+        if selected_user.totp_enabled {
+            info!("two-factor challenge issued for user: {}", selected_user.email);
+            return Ok(Some(LoginResponseDto::MfaRequired(create_mfa_challenge(&selected_user)?)));
         }
+        let selected_user = reset_login_failures(db, selected_user).await?;
+
+        let tokens = start_session_service(db, &selected_user, client).await?;
+        info!("token created successfully for user: {}", selected_user.email);
+        Ok(Some(LoginResponseDto::Tokens(tokens)))
     } else {
-        warn!("invalid password {:?}",dto.password);
+        warn!("invalid password for {}", selected_user.email);
+        record_login_failure(db, selected_user, client.ip_address).await?;
         Err(SystemError::PasswordError(PasswordError::InvalidPassword))
     }
 }
@@ -44,6 +61,8 @@ pub async fn create_user_service(db: &DatabaseConnection, dto: UserRequestDto) -
         return Err(SystemError::DuplicateError(dto.email));
     }
 
+    validate_password(&dto.password, &dto.name, &dto.email).map_err(SystemError::FieldValidationError)?;
+
     let hash_pw = match hash_password(&dto.password) {
         Ok(hash) => hash,
         Err(e) => {
@@ -56,13 +75,24 @@ pub async fn create_user_service(db: &DatabaseConnection, dto: UserRequestDto) -
         id: Set(Uuid::new_v4()),
         name: Set(dto.name),
         email: Set(dto.email),
-        role: Set("User".to_string()),
+        role: Set(UserRole::User),
         password: Set(hash_pw),
+        email_verified: Set(false),
+        failed_login_attempts: Set(0),
+        locked_until: Set(None),
+        totp_secret: Set(None),
+        totp_enabled: Set(false),
+        totp_last_step: Set(None),
+        tokens_revoked_at: Set(None),
     };
 
     match create_user_repo(db, new_user).await {
         Ok(user) => {
             info!("User successfully created: {:?}", user);
+            // the account exists either way, the user can ask for a new link
+            if let Err(e) = send_verification_mail_service(db, &user).await {
+                error!("Failed to send verification mail: {:?}", e);
+            }
             Ok(user)
         }
         Err(e) => {
@@ -72,7 +102,7 @@ pub async fn create_user_service(db: &DatabaseConnection, dto: UserRequestDto) -
     }
 }
 
-pub async fn update_user_service(db: &DatabaseConnection, id: String, dto: UserRequestDto) -> Result<Model, SystemError> {
+pub async fn update_user_service(db: &DatabaseConnection, id: String, dto: UserRequestDto, actor: &AuthenticatedUser) -> Result<Model, SystemError> {
     //convert String to uuid
     let user_id = match Uuid::parse_str(&id) {
         Ok(uuid) => uuid,
@@ -85,6 +115,8 @@ pub async fn update_user_service(db: &DatabaseConnection, id: String, dto: UserR
         return Err(SystemError::NotFoundError(id.to_string() + " id"));
     }
 
+    validate_password(&dto.password, &dto.name, &dto.email).map_err(SystemError::FieldValidationError)?;
+
     let update_hash_pw = match hash_password(&dto.password) {
         Ok(hash) => hash,
         Err(e) => {
@@ -93,7 +125,7 @@ pub async fn update_user_service(db: &DatabaseConnection, id: String, dto: UserR
         }
     };
 
-    let mut select_user = select_user.unwrap();
+    let select_user = select_user.unwrap();
     let mut active_user: user_model::ActiveModel = select_user.into();
 
     active_user.name = Set(dto.name);
@@ -102,7 +134,7 @@ pub async fn update_user_service(db: &DatabaseConnection, id: String, dto: UserR
 
     match update_user_repo(db, active_user).await {
         Ok(update_user) => {
-            info!("User successfully created: {:?}", update_user);
+            info!("User successfully updated by {}: {:?}", actor.email, update_user);
             Ok(update_user)
         }
         Err(e) => {
@@ -112,7 +144,7 @@ pub async fn update_user_service(db: &DatabaseConnection, id: String, dto: UserR
     }
 }
 
-pub async fn delete_user_service(db: &DatabaseConnection, id: String) -> Result<DeleteResult, SystemError> {
+pub async fn delete_user_service(db: &DatabaseConnection, id: String, actor: &AuthenticatedUser) -> Result<DeleteResult, SystemError> {
     let user_id = match Uuid::parse_str(&id) {
         Ok(uuid) => uuid,
         Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string()))
@@ -123,9 +155,15 @@ pub async fn delete_user_service(db: &DatabaseConnection, id: String) -> Result<
         return Err(SystemError::NotFoundError(id.to_string() + " id"));
     }
     let selected_user = selected_user.unwrap();
-    match delete_user_repo(db, selected_user).await {
+
+    let txn = db.begin().await?;
+    if is_admin(&txn, &selected_user).await? {
+        ensure_admin_remains(&txn, &selected_user).await?;
+    }
+    match delete_user_repo(&txn, selected_user).await {
         Ok(delete_user) => {
-            info!("User successfully deleted");
+            txn.commit().await?;
+            info!("User {} successfully deleted by {}", id, actor.email);
             Ok(delete_user)
         }
         Err(e) => {
@@ -135,6 +173,52 @@ pub async fn delete_user_service(db: &DatabaseConnection, id: String) -> Result<
     }
 }
 
+// only admins hand out or take away the admin role,
+// the user's tokens are revoked so the new role applies at once
+pub async fn change_role_service(db: &DatabaseConnection, id: String, dto: ChangeRoleRequestDto, actor: &AuthenticatedUser) -> Result<UserResponseDto, SystemError> {
+    let user_id = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err(SystemError::ValidationError("Invalid UUID format".to_string()))
+    };
+
+    let selected_user = User::find_by_id(user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(id.to_string() + " id"));
+    }
+    let selected_user = selected_user.unwrap();
+
+    if selected_user.role == dto.role {
+        return Ok(create_response_dto(&selected_user));
+    }
+    let admin_change = selected_user.role == UserRole::Admin || dto.role == UserRole::Admin;
+    if admin_change && !actor.roles.contains(&Role::Admin) {
+        warn!("{} may not change the admin role of {}", actor.email, selected_user.email);
+        return Err(SystemError::ValidationError("Only admins can grant or remove the admin role".to_string()));
+    }
+
+    let previous_role = selected_user.role;
+    let updated_user = assign_role(db, selected_user, dto.role).await?;
+    warn!("role of {} changed from {} to {} by {}", updated_user.email, previous_role.as_str(), updated_user.role.as_str(), actor.email);
+
+    let updated_user = revoke_all_user_tokens_service(db, id, actor).await?;
+    Ok(create_response_dto(&updated_user))
+}
+
+// set the role of the user, the last admin can't lose it
+pub async fn assign_role(db: &DatabaseConnection, user: Model, role: UserRole) -> Result<Model, SystemError> {
+    let txn = db.begin().await?;
+    // an admin who also holds Admin as an additional role stays admin
+    if user.role == UserRole::Admin && role != UserRole::Admin && find_user_role(&txn, user.id, Role::Admin.as_str()).await?.is_none() {
+        ensure_admin_remains(&txn, &user).await?;
+    }
+
+    let mut active_user: user_model::ActiveModel = user.into();
+    active_user.role = Set(role);
+    let updated_user = update_user_repo(&txn, active_user).await?;
+    txn.commit().await?;
+    Ok(updated_user)
+}
+
 pub async fn get_all_paginate_service(db: &DatabaseConnection, search_text: String, page: u64, size: u64) -> Result<PaginateUserResponseDto, SystemError> {
     let users = all_users_repo(db, &search_text, page, size).await?;
     let users_count = all_users_count_repo(db, &search_text).await?;
@@ -146,12 +230,134 @@ pub async fn get_all_paginate_service(db: &DatabaseConnection, search_text: Stri
     Ok(paginate_users)
 }
 
+pub async fn get_me_service(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<UserResponseDto, SystemError> {
+    let selected_user = find_me(db, actor).await?;
+    Ok(create_response_dto(&selected_user))
+}
+
+// a new email address has to be verified again, changing it takes the current password
+// like a password change and signs out the other sessions
+pub async fn update_me_service(db: &DatabaseConnection, dto: UpdateProfileRequestDto, actor: &AuthenticatedUser, client: ClientInfo) -> Result<UserResponseDto, SystemError> {
+    let selected_user = find_me(db, actor).await?;
+    let email_changed = dto.email.as_ref().is_some_and(|email| *email != selected_user.email);
+    if email_changed {
+        check_login_allowed(&selected_user)?;
+        let current_password = match &dto.current_password {
+            Some(password) if !password.is_empty() => password,
+            _ => return Err(SystemError::ValidationError("Current password is required to change the email".to_string())),
+        };
+        if !verify_password(current_password, &selected_user.password).unwrap_or(false) {
+            warn!("email change with invalid current password for {}", selected_user.email);
+            record_login_failure(db, selected_user, client.ip_address).await?;
+            return Err(SystemError::PasswordError(PasswordError::InvalidPassword));
+        }
+    }
+    if email_changed && find_user_by_email(db, dto.email.as_ref().unwrap()).await?.is_some() {
+        return Err(SystemError::DuplicateError(dto.email.unwrap()));
+    }
+
+    let mut active_user: user_model::ActiveModel = selected_user.into();
+    if let Some(name) = dto.name {
+        active_user.name = Set(name);
+    }
+    if email_changed {
+        active_user.email = Set(dto.email.unwrap());
+        active_user.email_verified = Set(false);
+    }
+
+    let updated_user = match update_user_repo(db, active_user).await {
+        Ok(user) => user,
+        Err(e) => {
+            error!("Failed to update profile: {:?}", e);
+            return Err(e);
+        }
+    };
+    info!("profile updated by user: {}", updated_user.email);
+
+    if email_changed {
+        let revoked = revoke_other_sessions_service(db, actor).await?;
+        info!("email changed by user: {} ({} other sessions revoked)", updated_user.email, revoked);
+        if let Err(e) = send_verification_mail_service(db, &updated_user).await {
+            error!("Failed to send verification mail: {:?}", e);
+        }
+    }
+    Ok(create_response_dto(&updated_user))
+}
+
+// wrong current passwords count as failed logins, a stolen token can't be used to guess it
+pub async fn change_password_service(db: &DatabaseConnection, dto: ChangePasswordRequestDto, actor: &AuthenticatedUser, client: ClientInfo) -> Result<(), SystemError> {
+    let selected_user = find_me(db, actor).await?;
+    check_login_allowed(&selected_user)?;
+
+    if !verify_password(&dto.current_password, &selected_user.password).unwrap_or(false) {
+        warn!("password change with invalid current password for {}", selected_user.email);
+        record_login_failure(db, selected_user, client.ip_address).await?;
+        return Err(SystemError::PasswordError(PasswordError::InvalidPassword));
+    }
+
+    validate_password(&dto.new_password, &selected_user.name, &selected_user.email).map_err(SystemError::FieldValidationError)?;
+    let hash_pw = match hash_password(&dto.new_password) {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Password hashing failed: {:?}", e);
+            return Err(SystemError::PasswordError(PasswordError::PasswordHashErr(e.to_string())));
+        }
+    };
+
+    let mut active_user: user_model::ActiveModel = selected_user.into();
+    active_user.password = Set(hash_pw);
+    active_user.failed_login_attempts = Set(0);
+    let updated_user = update_user_repo(db, active_user).await?;
+
+    let revoked = revoke_other_sessions_service(db, actor).await?;
+    info!("password changed by user: {} ({} other sessions revoked)", updated_user.email, revoked);
+    Ok(())
+}
+
+async fn find_me(db: &DatabaseConnection, actor: &AuthenticatedUser) -> Result<Model, SystemError> {
+    let selected_user = User::find_by_id(actor.user_id).one(db).await?;
+    if selected_user.is_none() {
+        return Err(SystemError::NotFoundError(actor.email.to_string()));
+    }
+    Ok(selected_user.unwrap())
+}
+
+// upgrade the stored hash to the current parameters while the plain password is at hand,
+// a failure only means the old hash stays in use
+async fn rehash_if_outdated(db: &DatabaseConnection, user: Model, password: &str) -> Model {
+    if !needs_rehash(&user.password) {
+        return user;
+    }
+
+    let hash_pw = match hash_password(password) {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Password rehash failed: {:?}", e);
+            return user;
+        }
+    };
+    let mut active_user: user_model::ActiveModel = user.clone().into();
+    active_user.password = Set(hash_pw);
+
+    match update_user_repo(db, active_user).await {
+        Ok(updated_user) => {
+            info!("password hash upgraded for user: {}", updated_user.email);
+            updated_user
+        }
+        Err(e) => {
+            error!("Failed to store rehashed password: {:?}", e);
+            user
+        }
+    }
+}
+
 fn create_response_dto(user: &Model) -> UserResponseDto {
     UserResponseDto {
         id: user.id,
         name: user.name.clone(),
         email: user.email.clone(),
-        role: user.role.clone(),
+        role: user.role,
+        email_verified: user.email_verified,
     }
 }
 
@@ -1,49 +1,165 @@
 use chrono::{Duration, Utc};
 use std::env;
 use actix_web::dev::ServiceRequest;
-use actix_web::{HttpMessage, HttpRequest};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use actix_web::HttpRequest;
+use jsonwebtoken::{decode, decode_header, encode, Header, TokenData, Validation};
+use jsonwebtoken::errors::ErrorKind;
+use log::{error, warn};
+use sea_orm::DatabaseConnection;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde::de::Error;
-use crate::midleware::permission::{has_permission, Permission, Role};
+use uuid::Uuid;
+use crate::midleware::authenticated_user::AuthenticatedUser;
+use crate::midleware::permission::is_known_role;
+use crate::services::service_account_service::{authenticate_api_key, API_KEY_PREFIX};
+use crate::services::session_service::record_session_use_service;
+use crate::services::token_service::is_token_revoked_service;
+use crate::utill::jwt_keys::{find_key, signing_key};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,      // Subject (username)
+    pub uid: Uuid,        // user id
     pub roles: Vec<String>,      // role (user role)
     pub exp: usize,        // Expiration timestamp
+    pub iat: usize,        // Issued at timestamp
+    pub jti: String,       // Unique token id, used for revocation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub restricted: bool,  // email not verified yet, no route guard lets it through
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>, // session the token belongs to, revoking the session rejects the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Impersonator>, // the admin acting as this user, see impersonate_user_service
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,   // only safe methods and read permissions pass
+}
+
+// actor claim of an impersonation token, who really makes the requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Impersonator {
+    pub sub: String,
+    pub uid: Uuid,
 }
 
 
 impl Claims {
-    pub fn new(use_email: String, user_roles: Vec<String>) -> Self {
-        let expiration = Utc::now() + Duration::hours(24); // Token valid for 24 hours
+    pub fn new(user_id: Uuid, use_email: String, user_roles: Vec<String>) -> Self {
+        let now = Utc::now();
+        let expiration = now + access_token_ttl(); // short-lived access token
         Self {
             sub: use_email.to_string(),
+            uid: user_id,
             roles: user_roles,
             exp: expiration.timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            restricted: false,
+            sid: None,
+            act: None,
+            read_only: false,
+        }
+    }
+}
+
+// short lived proof that the password was right, exchanged together with a TOTP code at /login/mfa
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MfaChallengeClaims {
+    pub sub: String,
+    pub uid: Uuid,
+    pub mfa: bool,         // access token claims don't have it, so neither token passes for the other
+    pub exp: usize,
+    pub iat: usize,
+    pub jti: String,
+}
+
+impl MfaChallengeClaims {
+    pub fn new(user_id: Uuid, use_email: String) -> Self {
+        let now = Utc::now();
+        let expiration = now + mfa_challenge_ttl();
+        Self {
+            sub: use_email,
+            uid: user_id,
+            mfa: true,
+            exp: expiration.timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
         }
     }
 }
 
-// Generate a JWT token
-pub fn create_token(user_email: String, user_role: Vec<String>) -> Result<String, jsonwebtoken::errors::Error> {
-    let secret = env::var("SECRET_KEY").expect("JWT_SECRET must be set");
-    let claims = Claims::new(user_email, user_role);
-    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref()))
+// access token lifetime, ACCESS_TOKEN_TTL_MINUTES (default 15 minutes)
+pub fn access_token_ttl() -> Duration {
+    let minutes = env::var("ACCESS_TOKEN_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(15);
+    Duration::minutes(minutes)
 }
 
-// verify a JWT token
+// impersonation token lifetime, IMPERSONATION_TTL_MINUTES (default 10 minutes), there is no refresh
+pub fn impersonation_ttl() -> Duration {
+    let minutes = env::var("IMPERSONATION_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(10);
+    Duration::minutes(minutes)
+}
+
+// refresh token lifetime, REFRESH_TOKEN_TTL_DAYS (default 7 days)
+pub fn refresh_token_ttl() -> Duration {
+    let days = env::var("REFRESH_TOKEN_TTL_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(7);
+    Duration::days(days)
+}
+
+// mfa challenge lifetime, MFA_CHALLENGE_TTL_MINUTES (default 5 minutes)
+pub fn mfa_challenge_ttl() -> Duration {
+    let minutes = env::var("MFA_CHALLENGE_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(5);
+    Duration::minutes(minutes)
+}
+
+// Generate a JWT token, signed with the current key
+pub fn create_token<T: Serialize>(claims: &T) -> Result<String, jsonwebtoken::errors::Error> {
+    let key = signing_key();
+
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+    encode(&header, claims, &key.encoding_key)
+}
+
+// verify a JWT token with the key named in its header
 pub fn verify_token(token: &str) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
-    let secret = env::var("SECRET_KEY").expect("JWT_SECRET must be set");
-    decode::<Claims>(
+    decode_token(token)
+}
+
+pub fn verify_mfa_challenge_token(token: &str) -> Result<TokenData<MfaChallengeClaims>, jsonwebtoken::errors::Error> {
+    decode_token(token)
+}
+
+fn decode_token<T: DeserializeOwned>(token: &str) -> Result<TokenData<T>, jsonwebtoken::errors::Error> {
+    let header = decode_header(token)?;
+    let key = find_key(header.kid.as_deref()).ok_or(ErrorKind::InvalidKeyFormat)?;
+    decode::<T>(
         token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
+        &key.decoding_key,
+        &Validation::new(key.algorithm),
     )
 }
 
 // get token
+// API keys come in the X-API-Key header or as a bearer token with the key prefix
+fn extract_api_key(req: &HttpRequest) -> Option<String> {
+    if let Some(api_key) = req.headers().get("X-API-Key").and_then(|header| header.to_str().ok()) {
+        return Some(api_key.to_string());
+    }
+    extract_token(req).filter(|token| token.starts_with(API_KEY_PREFIX))
+}
+
 fn extract_token(req: &HttpRequest) -> Option<String> {
     req.headers()
         .get("Authorization")
@@ -53,59 +169,47 @@ fn extract_token(req: &HttpRequest) -> Option<String> {
 }
 
 
-// check role from token
-pub fn extract_and_check_role_from_token(req: &ServiceRequest) -> bool {
+// verify the request API key or token and check its role, revoked tokens are rejected
+pub async fn authenticate_request(req: &ServiceRequest, db: &DatabaseConnection) -> Option<AuthenticatedUser> {
     // Create a HttpRequest from ServiceRequest
     let http = req.request();
 
-    if let Some(token_String) = extract_token(http) {
-        let token = token_String.as_ref();
-
-        if let Ok(data) = verify_token(token) {
-            let claims = data.claims;
-
-            let allowed_roles = vec![Role::Admin, Role::User, Role::Student];
-            return claims.roles.iter()
-                .filter_map(|r| Role::from_str(r)) // Parse string to Role
-                .any(|role| allowed_roles.contains(&role));
-        }
+    if let Some(api_key) = extract_api_key(http) {
+        return match authenticate_api_key(db, &api_key).await {
+            Ok(user) => user,
+            Err(e) => {
+                error!("api key check failed: {:?}", e);
+                None
+            }
+        };
     }
-    false
-}
 
+    let token_string = extract_token(http)?;
+    let claims = verify_token(&token_string).ok()?.claims;
 
-// Check if a user has any of the specified roles
-
-fn has_any_role(req: &HttpRequest, allowed_roles: &Vec<Role>) -> bool {
-    if let Some(token_string) = extract_token(req) {
-        if let Ok(data) = verify_token(&token_string) {
-            let claims = data.claims;
-
-            // Convert String roles in the token to Role enums
-            let user_roles: Vec<Role> = claims
-                .roles
-                .iter()
-                .filter_map(|role| Role::from_str(role)) // Parse string to Role
-                .collect();
-
-            // Check if the user has at least one allowed role
-            return allowed_roles.iter().any(|role| user_roles.contains(role));
+    match is_token_revoked_service(db, &claims).await {
+        Ok(false) => {
+            if let Some(session_id) = claims.sid {
+                if let Err(e) = record_session_use_service(db, session_id).await {
+                    error!("session use not recorded: {:?}", e);
+                }
+            }
+        }
+        Ok(true) => {
+            warn!("revoked token rejected: {}", claims.jti);
+            return None;
+        }
+        Err(e) => {
+            error!("token revocation check failed: {:?}", e);
+            return None;
         }
     }
-    false
-}
-
 
-// Check if a user has a specific permission
-pub fn has_permission_with_roles(req: &HttpRequest, roles: &Vec<Role>, permission: &Permission) -> bool {
-    if has_any_role(req, roles) {
-        // Check permissions if the role is allowed
-        if roles.iter().any(|role| has_permission(&role, &permission)) {
-            return true;
+    let user = AuthenticatedUser::from_claims(claims);
+    for role in &user.roles {
+        if is_known_role(db, role).await {
+            return Some(user);
         }
     }
-    false
+    None
 }
-
-
-
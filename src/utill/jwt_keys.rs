@@ -0,0 +1,315 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration as StdDuration, Instant};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use jsonwebtoken::jwk::{AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use log::{error, info};
+use rand_core::OsRng;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use uuid::Uuid;
+use crate::exceptions::errors::JwtError;
+
+/*
+JWT signing keys
+
+JWT_ALGORITHM            HS256 (default, signed with SECRET_KEY), RS256 or EdDSA
+JWT_KEYS_DIR             directory of PEM private keys, file name = kid, the last file in name order signs
+JWT_KEY_ROTATION_HOURS   generate a new signing key every N hours (asymmetric only, 0 = off)
+JWT_MAX_KEYS             how many keys stay valid for verification (default 3)
+*/
+
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding_key: EncodingKey,
+    pub decoding_key: DecodingKey,
+    pub jwk: Option<Jwk>, // public part, shared secrets are never published
+}
+
+// keys ordered from oldest to newest, the newest one signs new tokens
+static KEYS: LazyLock<RwLock<Vec<Arc<JwtKey>>>> = LazyLock::new(|| {
+    RwLock::new(load_keys().expect("Failed to load JWT signing keys"))
+});
+
+// unknown kids cost a read of the key directory, so it is read at most once per interval
+const KEY_LOOKUP_INTERVAL: StdDuration = StdDuration::from_secs(10);
+static LAST_KEY_LOOKUP: Mutex<Option<Instant>> = Mutex::new(None);
+
+
+// load the keys at startup so configuration errors show up immediately
+pub fn init_keys() {
+    let keys = KEYS.read().unwrap();
+    let kids: Vec<&String> = keys.iter().map(|key| &key.kid).collect();
+    info!("JWT signing keys loaded: {:?}", kids);
+}
+
+pub fn signing_key() -> Arc<JwtKey> {
+    KEYS.read().unwrap().last().cloned().expect("no JWT signing key available")
+}
+
+// tokens without a kid were issued before key rotation and use the signing key,
+// an unknown kid may be a key another instance rotated in, it is looked up in the key directory
+pub fn find_key(kid: Option<&str>) -> Option<Arc<JwtKey>> {
+    let found = {
+        let keys = KEYS.read().unwrap();
+        match kid {
+            Some(kid) => keys.iter().find(|key| key.kid == kid).cloned(),
+            None => keys.last().cloned(),
+        }
+    };
+    match (found, kid) {
+        (Some(key), _) => Some(key),
+        (None, Some(kid)) => load_rotated_key(kid),
+        (None, None) => None,
+    }
+}
+
+// public keys for other services to verify our tokens
+pub fn jwks() -> JwkSet {
+    let keys = KEYS.read().unwrap();
+    JwkSet {
+        keys: keys.iter().filter_map(|key| key.jwk.clone()).collect(),
+    }
+}
+
+// add a fresh signing key, older keys stay valid for verification until they drop out
+pub fn rotate_keys() -> Result<String, JwtError> {
+    let algorithm = algorithm_from_env();
+    if algorithm == Algorithm::HS256 {
+        return Err(JwtError::KeyError("shared secret keys can not be rotated".to_string()));
+    }
+
+    let key = generate_key(algorithm)?;
+    let kid = key.kid.clone();
+
+    let mut keys = KEYS.write().unwrap();
+    keys.push(Arc::new(key));
+    let max_keys = max_keys();
+    if keys.len() > max_keys {
+        let expired = keys.len() - max_keys;
+        keys.drain(..expired);
+    }
+
+    info!("JWT signing key rotated, new kid: {}", kid);
+    Ok(kid)
+}
+
+// rotate the signing key on the configured schedule
+pub async fn schedule_key_rotation() {
+    let hours = env::var("JWT_KEY_ROTATION_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(0);
+    if hours == 0 || algorithm_from_env() == Algorithm::HS256 {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(StdDuration::from_secs(hours * 60 * 60));
+    interval.tick().await; // the first tick completes immediately
+    loop {
+        interval.tick().await;
+        if let Err(e) = rotate_keys() {
+            error!("JWT key rotation failed: {:?}", e);
+        }
+    }
+}
+
+
+fn algorithm_from_env() -> Algorithm {
+    match env::var("JWT_ALGORITHM").unwrap_or("HS256".to_string()).as_str() {
+        "HS256" => Algorithm::HS256,
+        "RS256" => Algorithm::RS256,
+        "EdDSA" => Algorithm::EdDSA,
+        other => panic!("Unsupported JWT_ALGORITHM: {}", other),
+    }
+}
+
+fn keys_dir() -> Option<PathBuf> {
+    env::var("JWT_KEYS_DIR").ok().map(PathBuf::from)
+}
+
+fn max_keys() -> usize {
+    env::var("JWT_MAX_KEYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(3)
+}
+
+// time based prefix keeps generated keys in creation order
+fn new_kid() -> String {
+    let suffix = Uuid::new_v4().simple().to_string();
+    format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S"), &suffix[..8])
+}
+
+fn load_keys() -> Result<Vec<Arc<JwtKey>>, JwtError> {
+    let algorithm = algorithm_from_env();
+    if algorithm == Algorithm::HS256 {
+        let secret = env::var("SECRET_KEY").expect("JWT_SECRET must be set");
+        let key = JwtKey {
+            kid: "default".to_string(),
+            algorithm,
+            encoding_key: EncodingKey::from_secret(secret.as_ref()),
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+            jwk: None,
+        };
+        return Ok(vec![Arc::new(key)]);
+    }
+
+    let mut keys = Vec::new();
+    if let Some(dir) = keys_dir() {
+        let entries = fs::read_dir(&dir).map_err(|e| JwtError::KeyError(format!("{}: {}", dir.display(), e)))?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pem"))
+            .collect();
+        paths.sort();
+
+        for path in paths.iter().skip(paths.len().saturating_sub(max_keys())) {
+            let kid = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let pem = fs::read_to_string(path).map_err(|e| JwtError::KeyError(e.to_string()))?;
+            keys.push(Arc::new(key_from_pem(kid, algorithm, &pem)?));
+        }
+    }
+
+    if keys.is_empty() {
+        keys.push(Arc::new(generate_key(algorithm)?));
+    }
+    Ok(keys)
+}
+
+// a key written by another instance, it signs here too when it is newer than ours,
+// tokens with made up kids must not turn into a file read per request
+fn load_rotated_key(kid: &str) -> Option<Arc<JwtKey>> {
+    if kid.is_empty() || !kid.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return None;
+    }
+    let algorithm = algorithm_from_env();
+    if algorithm == Algorithm::HS256 {
+        return None;
+    }
+    {
+        let mut last_lookup = LAST_KEY_LOOKUP.lock().unwrap();
+        if last_lookup.is_some_and(|at| at.elapsed() < KEY_LOOKUP_INTERVAL) {
+            return None;
+        }
+        *last_lookup = Some(Instant::now());
+    }
+    let pem = fs::read_to_string(keys_dir()?.join(format!("{}.pem", kid))).ok()?;
+    let key = match key_from_pem(kid.to_string(), algorithm, &pem) {
+        Ok(key) => Arc::new(key),
+        Err(e) => {
+            error!("JWT key {} could not be loaded: {:?}", kid, e);
+            return None;
+        }
+    };
+
+    let mut keys = KEYS.write().unwrap();
+    if let Some(existing) = keys.iter().find(|existing| existing.kid == kid) {
+        return Some(existing.clone());
+    }
+    let position = keys.iter().position(|existing| existing.kid.as_str() > kid).unwrap_or(keys.len());
+    keys.insert(position, key.clone());
+    let max_keys = max_keys();
+    if keys.len() > max_keys {
+        let expired = keys.len() - max_keys;
+        keys.drain(..expired);
+    }
+    info!("JWT key {} loaded from the key directory", kid);
+    Some(key)
+}
+
+// generate a new key and persist it, restarts load it and other instances
+// pick it up from the key directory the first time they see its kid
+fn generate_key(algorithm: Algorithm) -> Result<JwtKey, JwtError> {
+    let kid = new_kid();
+    let pem = generate_pem(algorithm)?;
+    if let Some(dir) = keys_dir() {
+        // private keys are only readable by the service user
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(dir.join(format!("{}.pem", kid)))
+            .map_err(|e| JwtError::KeyError(e.to_string()))?;
+        file.write_all(pem.as_bytes()).map_err(|e| JwtError::KeyError(e.to_string()))?;
+    }
+    key_from_pem(kid, algorithm, &pem)
+}
+
+fn generate_pem(algorithm: Algorithm) -> Result<String, JwtError> {
+    match algorithm {
+        Algorithm::EdDSA => {
+            let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+                .map_err(|e| JwtError::KeyError(e.to_string()))?;
+            Ok(pem::encode(&pem::Pem::new("PRIVATE KEY", document.as_ref())))
+        }
+        Algorithm::RS256 => {
+            let private_key = RsaPrivateKey::new(&mut OsRng, 2048).map_err(|e| JwtError::KeyError(e.to_string()))?;
+            let pem = private_key.to_pkcs8_pem(LineEnding::LF).map_err(|e| JwtError::KeyError(e.to_string()))?;
+            Ok(pem.to_string())
+        }
+        other => Err(JwtError::KeyError(format!("unsupported algorithm {:?}", other))),
+    }
+}
+
+fn key_from_pem(kid: String, algorithm: Algorithm, pem: &str) -> Result<JwtKey, JwtError> {
+    let (encoding_key, parameters, key_algorithm) = match algorithm {
+        Algorithm::EdDSA => {
+            let encoding_key = EncodingKey::from_ed_pem(pem.as_bytes()).map_err(|e| JwtError::KeyError(e.to_string()))?;
+            let document = pem::parse(pem).map_err(|e| JwtError::KeyError(e.to_string()))?;
+            let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(document.contents())
+                .map_err(|e| JwtError::KeyError(e.to_string()))?;
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()),
+            });
+            (encoding_key, parameters, KeyAlgorithm::EdDSA)
+        }
+        Algorithm::RS256 => {
+            let encoding_key = EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| JwtError::KeyError(e.to_string()))?;
+            let private_key = RsaPrivateKey::from_pkcs8_pem(pem)
+                .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+                .map_err(|e| JwtError::KeyError(e.to_string()))?;
+            let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(private_key.n().to_bytes_be()),
+                e: URL_SAFE_NO_PAD.encode(private_key.e().to_bytes_be()),
+            });
+            (encoding_key, parameters, KeyAlgorithm::RS256)
+        }
+        other => return Err(JwtError::KeyError(format!("unsupported algorithm {:?}", other))),
+    };
+
+    let jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.clone()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    };
+    let decoding_key = DecodingKey::from_jwk(&jwk).map_err(|e| JwtError::KeyError(e.to_string()))?;
+
+    Ok(JwtKey {
+        kid,
+        algorithm,
+        encoding_key,
+        decoding_key,
+        jwk: Some(jwk),
+    })
+}
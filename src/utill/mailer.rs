@@ -0,0 +1,88 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use chrono::Utc;
+use log::info;
+use uuid::Uuid;
+use crate::exceptions::errors::SystemError;
+
+/*
+Outgoing mail
+
+MAIL_TRANSPORT   file (default), writes every message into MAIL_DIR instead of sending it
+MAIL_DIR         directory of the file transport (default ./mail)
+MAIL_FROM        sender address (default no-reply@localhost)
+APP_URL          base url used for links in mails (default http://localhost:8080)
+PASSWORD_RESET_URL  page of the frontend that asks for the new password, the mailed link adds ?token=...
+                    (without it the reset mail only carries the token)
+*/
+
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// a mail transport, add an implementation and a MAIL_TRANSPORT value to plug in another one
+pub trait Mailer: Send + Sync {
+    fn send(&self, mail: &Mail) -> Result<(), SystemError>;
+}
+
+// writes each message as an .eml file, used in development and tests
+pub struct FileMailer {
+    dir: PathBuf,
+    from: String,
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, mail: &Mail) -> Result<(), SystemError> {
+        fs::create_dir_all(&self.dir).map_err(|e| SystemError::MailError(e.to_string()))?;
+
+        let now = Utc::now();
+        let file = self.dir.join(format!("{}-{}.eml", now.format("%Y%m%dT%H%M%S%.3f"), Uuid::new_v4().simple()));
+        let message = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\n\r\n{}\r\n",
+            self.from, mail.to, mail.subject, now.to_rfc2822(), mail.body
+        );
+        fs::write(&file, message).map_err(|e| SystemError::MailError(e.to_string()))?;
+
+        info!("mail to {} written to {}", mail.to, file.display());
+        Ok(())
+    }
+}
+
+static MAILER: LazyLock<Box<dyn Mailer>> = LazyLock::new(|| {
+    let from = env::var("MAIL_FROM").unwrap_or("no-reply@localhost".to_string());
+    match env::var("MAIL_TRANSPORT").unwrap_or("file".to_string()).as_str() {
+        "file" => Box::new(FileMailer {
+            dir: PathBuf::from(env::var("MAIL_DIR").unwrap_or("mail".to_string())),
+            from,
+        }),
+        other => panic!("Unsupported MAIL_TRANSPORT: {}", other),
+    }
+});
+
+
+// set up the transport at startup so a bad MAIL_TRANSPORT shows up immediately
+pub fn init_mailer() {
+    LazyLock::force(&MAILER);
+    info!("mail transport: {}", env::var("MAIL_TRANSPORT").unwrap_or("file".to_string()));
+}
+
+pub fn send_mail(mail: Mail) -> Result<(), SystemError> {
+    MAILER.send(&mail)
+}
+
+// absolute link to a path of this service
+pub fn app_link(path: &str) -> String {
+    let base_url = env::var("APP_URL").unwrap_or("http://localhost:8080".to_string());
+    format!("{}{}", base_url.trim_end_matches('/'), path)
+}
+
+// link to the frontend page that resets the password, the API itself only takes a POST
+pub fn password_reset_link(token: &str) -> Option<String> {
+    let url = env::var("PASSWORD_RESET_URL").ok().filter(|url| !url.is_empty())?;
+    let separator = if url.contains('?') { '&' } else { '?' };
+    Some(format!("{}{}token={}", url, separator, token))
+}
@@ -1,4 +1,10 @@
 pub mod jwt;
-pub mod bcrypt;
+pub mod jwt_keys;
+pub mod password_hash;
 pub mod validator;
-pub mod generic_response;
\ No newline at end of file
+pub mod generic_response;
+pub mod secure_token;
+pub mod mailer;
+pub mod totp;
+pub mod password_policy;
+pub mod oidc;
\ No newline at end of file
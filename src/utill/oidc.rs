@@ -0,0 +1,285 @@
+use std::env;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use log::{info, warn};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use crate::exceptions::errors::SystemError;
+use crate::midleware::permission::Role;
+use crate::utill::mailer::app_link;
+
+/*
+Single sign-on with an OpenID Connect provider (authorization code flow with PKCE)
+
+OIDC_ISSUER          issuer url, the provider is discovered at {issuer}/.well-known/openid-configuration
+OIDC_CLIENT_ID       client id registered at the provider, sign-in is disabled while this or the issuer is unset
+OIDC_CLIENT_SECRET   client secret, leave unset for a public client
+OIDC_REDIRECT_URI    callback registered at the provider (default APP_URL/oidc/callback)
+OIDC_SCOPES          requested scopes (default "openid email profile")
+OIDC_GROUPS_CLAIM    id token claim with the user's groups (default groups)
+OIDC_ROLE_MAPPING    group=Role pairs, e.g. "lms-admins=Admin,staff=User", the first pair matching a group wins
+OIDC_DEFAULT_ROLE    role of new users without a mapped group, unset refuses them
+*/
+
+pub struct OidcConfig {
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: String,
+}
+
+// the identity of a verified id token
+pub struct OidcIdentity {
+    pub subject: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub groups: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenEndpointResponse {
+    id_token: Option<String>,
+}
+
+struct Provider {
+    metadata: ProviderMetadata,
+    jwks: JwkSet,
+}
+
+struct CachedProvider {
+    loaded_at: Instant,
+    issuer: String,
+    provider: Arc<Provider>,
+}
+
+// discovery document and signing keys of the provider, reloaded hourly or when an unknown key shows up
+static PROVIDER: LazyLock<RwLock<Option<CachedProvider>>> = LazyLock::new(|| RwLock::new(None));
+
+const PROVIDER_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+// asymmetric only, a symmetric algorithm would verify with the public key as the secret
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA];
+
+static HTTP: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("Failed to build the OIDC http client")
+});
+
+
+fn env_value(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+pub fn oidc_config() -> Option<OidcConfig> {
+    Some(OidcConfig {
+        issuer: env_value("OIDC_ISSUER")?.trim_end_matches('/').to_string(),
+        client_id: env_value("OIDC_CLIENT_ID")?,
+        client_secret: env_value("OIDC_CLIENT_SECRET"),
+        redirect_uri: env_value("OIDC_REDIRECT_URI").unwrap_or_else(|| app_link("/oidc/callback")),
+        scopes: env_value("OIDC_SCOPES").unwrap_or("openid email profile".to_string()),
+    })
+}
+
+// S256 code challenge sent in place of the verifier
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+// the role for the user's groups, the first pair of OIDC_ROLE_MAPPING that matches a group wins
+pub fn role_for_groups(groups: &[String]) -> Option<Role> {
+    let mapping = env_value("OIDC_ROLE_MAPPING").unwrap_or_default();
+    mapping
+        .split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(group, _)| groups.iter().any(|g| g == group.trim()))
+        .and_then(|(_, role)| Role::from_str(role.trim()))
+}
+
+pub fn default_role() -> Option<Role> {
+    env_value("OIDC_DEFAULT_ROLE").and_then(|role| Role::from_str(role.trim()))
+}
+
+pub async fn authorization_url(config: &OidcConfig, state: &str, nonce: &str, code_challenge: &str) -> Result<String, SystemError> {
+    let provider = load_provider(config, false).await?;
+    let url = Url::parse_with_params(&provider.metadata.authorization_endpoint, &[
+        ("response_type", "code"),
+        ("client_id", config.client_id.as_str()),
+        ("redirect_uri", config.redirect_uri.as_str()),
+        ("scope", config.scopes.as_str()),
+        ("state", state),
+        ("nonce", nonce),
+        ("code_challenge", code_challenge),
+        ("code_challenge_method", "S256"),
+    ]).map_err(|e| SystemError::OidcProviderError(format!("invalid authorization endpoint: {}", e)))?;
+    Ok(url.to_string())
+}
+
+// trade the authorization code for the id token
+pub async fn exchange_code(config: &OidcConfig, code: &str, code_verifier: &str) -> Result<String, SystemError> {
+    let provider = load_provider(config, false).await?;
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", config.redirect_uri.as_str()),
+        ("client_id", config.client_id.as_str()),
+        ("code_verifier", code_verifier),
+    ];
+    if let Some(secret) = &config.client_secret {
+        form.push(("client_secret", secret.as_str()));
+    }
+
+    let response = HTTP.post(&provider.metadata.token_endpoint).form(&form).send().await
+        .map_err(|e| SystemError::OidcProviderError(e.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        warn!("OIDC code exchange rejected: {} {}", status, body);
+        return Err(SystemError::OidcError("the authorization code was rejected".to_string()));
+    }
+
+    let tokens: TokenEndpointResponse = response.json().await
+        .map_err(|e| SystemError::OidcProviderError(format!("invalid token response: {}", e)))?;
+    tokens.id_token.ok_or(SystemError::OidcProviderError("no id token in the token response".to_string()))
+}
+
+// check signature, issuer, audience, expiry and nonce of the id token
+pub async fn verify_id_token(config: &OidcConfig, id_token: &str, nonce: &str) -> Result<OidcIdentity, SystemError> {
+    let header = decode_header(id_token).map_err(|e| SystemError::OidcError(format!("invalid id token: {}", e)))?;
+    let kid = header.kid.clone().unwrap_or_default();
+
+    let mut provider = load_provider(config, false).await?;
+    if find_provider_key(&provider, &kid).is_none() {
+        // the provider may have rotated its keys
+        provider = load_provider(config, true).await?;
+    }
+    let jwk = find_provider_key(&provider, &kid)
+        .ok_or(SystemError::OidcError(format!("unknown signing key {}", kid)))?;
+
+    // the algorithm comes from the key, the token header only has to agree with it
+    let algorithm = match jwk.common.key_algorithm {
+        Some(key_algorithm) => Algorithm::from_str(&key_algorithm.to_string()).ok(),
+        None => Some(header.alg),
+    }
+    .filter(|algorithm| ALLOWED_ALGORITHMS.contains(algorithm));
+    let algorithm = match algorithm {
+        Some(algorithm) if algorithm == header.alg => algorithm,
+        _ => return Err(SystemError::OidcError(format!("id token algorithm {:?} is not allowed for key {}", header.alg, kid))),
+    };
+    let decoding_key = DecodingKey::from_jwk(jwk)
+        .map_err(|e| SystemError::OidcProviderError(format!("unusable signing key {}: {}", kid, e)))?;
+
+    let mut validation = Validation::new(algorithm);
+    validation.set_issuer(&[provider.metadata.issuer.as_str()]);
+    validation.set_audience(&[config.client_id.as_str()]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+    let claims = decode::<Map<String, Value>>(id_token, &decoding_key, &validation)
+        .map_err(|e| SystemError::OidcError(format!("invalid id token: {}", e)))?
+        .claims;
+
+    if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+        return Err(SystemError::OidcError("id token nonce does not match".to_string()));
+    }
+    let email = claims.get("email").and_then(Value::as_str)
+        .ok_or(SystemError::OidcError("id token has no email".to_string()))?;
+    if claims.get("email_verified").and_then(Value::as_bool) == Some(false) {
+        return Err(SystemError::OidcError("email address is not verified at the identity provider".to_string()));
+    }
+
+    let groups_claim = env_value("OIDC_GROUPS_CLAIM").unwrap_or("groups".to_string());
+    let groups = match claims.get(&groups_claim) {
+        Some(Value::Array(groups)) => groups.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(group)) => vec![group.clone()],
+        _ => vec![],
+    };
+
+    Ok(OidcIdentity {
+        subject: claims.get("sub").and_then(Value::as_str).unwrap_or_default().to_string(),
+        email: email.to_lowercase(),
+        name: claims.get("name").and_then(Value::as_str).map(str::to_string),
+        groups,
+    })
+}
+
+
+fn find_provider_key<'a>(provider: &'a Provider, kid: &str) -> Option<&'a Jwk> {
+    let jwks = &provider.jwks;
+
+    // a provider with a single key may leave out the kid
+    match kid {
+        "" if jwks.keys.len() == 1 => jwks.keys.first(),
+        kid => jwks.find(kid),
+    }
+}
+
+// fetch discovery document and keys unless a fresh copy for this issuer is cached
+async fn load_provider(config: &OidcConfig, force: bool) -> Result<Arc<Provider>, SystemError> {
+    if !force {
+        if let Some(cached) = PROVIDER.read().unwrap().as_ref() {
+            if cached.issuer == config.issuer && cached.loaded_at.elapsed() < PROVIDER_CACHE_TTL {
+                return Ok(cached.provider.clone());
+            }
+        }
+    }
+
+    let discovery_url = format!("{}/.well-known/openid-configuration", config.issuer);
+    let metadata: ProviderMetadata = fetch_json(&discovery_url).await?;
+    if metadata.issuer.trim_end_matches('/') != config.issuer {
+        return Err(SystemError::OidcProviderError(format!("discovery names issuer {}, expected {}", metadata.issuer, config.issuer)));
+    }
+    let jwks: JwkSet = fetch_json(&metadata.jwks_uri).await?;
+
+    info!("loaded OIDC provider {} with {} signing keys", metadata.issuer, jwks.keys.len());
+    let provider = Arc::new(Provider { metadata, jwks });
+    *PROVIDER.write().unwrap() = Some(CachedProvider {
+        loaded_at: Instant::now(),
+        issuer: config.issuer.clone(),
+        provider: provider.clone(),
+    });
+    Ok(provider)
+}
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, SystemError> {
+    let response = HTTP.get(url).send().await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| SystemError::OidcProviderError(format!("{}: {}", url, e)))?;
+    response.json().await
+        .map_err(|e| SystemError::OidcProviderError(format!("{}: {}", url, e)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // S256 of "abc", the digest is the FIPS 180-2 example ba7816bf...f20015ad
+    #[test]
+    fn pkce_challenge_is_the_sha256_of_the_verifier() {
+        assert_eq!(pkce_challenge("abc"), "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0");
+    }
+
+    #[test]
+    fn pkce_challenge_is_unpadded_url_safe_base64() {
+        let challenge = pkce_challenge("any verifier");
+        assert_eq!(challenge.len(), 43);
+        assert!(challenge.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+}
@@ -0,0 +1,106 @@
+use std::env;
+use std::sync::LazyLock;
+use argon2::{Algorithm, Argon2, KeyId, Params, ParamsBuilder, PasswordHasher, PasswordHash, PasswordVerifier, Version, password_hash::SaltString};
+use rand_core::OsRng; // Secure random number generator
+
+/*
+Password hashing, Argon2id
+
+ARGON2_MEMORY_KIB    memory cost in KiB (default 19456)
+ARGON2_ITERATIONS    time cost (default 2)
+ARGON2_PARALLELISM   lanes (default 1)
+PASSWORD_PEPPER      optional server-side secret mixed into new hashes, keep it out of the database,
+                     peppered hashes no longer verify once it is removed or changed
+*/
+
+// peppered hashes carry this key id, so hashes from before the pepper still verify
+const PEPPER_KEY_ID: &[u8] = b"pepper";
+
+struct HashConfig {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    pepper: Option<Vec<u8>>,
+}
+
+static CONFIG: LazyLock<HashConfig> = LazyLock::new(|| HashConfig {
+    memory_kib: env_value("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST),
+    iterations: env_value("ARGON2_ITERATIONS", Params::DEFAULT_T_COST),
+    parallelism: env_value("ARGON2_PARALLELISM", Params::DEFAULT_P_COST),
+    pepper: env::var("PASSWORD_PEPPER").ok().filter(|pepper| !pepper.is_empty()).map(String::into_bytes),
+});
+
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    // Generate a random salt
+    let salt = SaltString::generate(&mut OsRng);
+
+    // Use the configured Argon2 parameters
+    let config = &*CONFIG;
+    let mut params = ParamsBuilder::new();
+    params.m_cost(config.memory_kib).t_cost(config.iterations).p_cost(config.parallelism);
+    if config.pepper.is_some() {
+        params.keyid(KeyId::new(PEPPER_KEY_ID)?);
+    }
+    let argon2 = argon2(config.pepper.as_deref(), params.build()?)?;
+
+    // Hash the password
+    let password_hash = argon2.hash_password(password.as_bytes(), &salt)?;
+
+    // Return the hashed password as a string
+    Ok(password_hash.to_string())
+}
+
+
+pub fn verify_password(password: &str, hashed_password: &str) -> Result<bool, argon2::password_hash::Error> {
+    // Parse the hashed password
+    let parsed_hash = PasswordHash::new(hashed_password)?;
+
+    // the parameters come from the hash, only the pepper has to be picked
+    let pepper = if Params::try_from(&parsed_hash)?.keyid() == PEPPER_KEY_ID {
+        Some(CONFIG.pepper.as_deref().ok_or(argon2::password_hash::Error::Crypto)?)
+    } else {
+        None
+    };
+    let argon2 = argon2(pepper, Params::default())?;
+
+    // Return true if password is correct, otherwise return the actual error
+    match argon2.verify_password(password.as_bytes(), &parsed_hash) {
+        Ok(_) => Ok(true),
+        Err(e) => Err(e), // Return the actual error
+    }
+}
+
+// true when the hash was made with other parameters or pepper setting than configured now
+pub fn needs_rehash(hashed_password: &str) -> bool {
+    let parsed_hash = match PasswordHash::new(hashed_password) {
+        Ok(hash) => hash,
+        Err(_) => return true,
+    };
+    let params = match Params::try_from(&parsed_hash) {
+        Ok(params) => params,
+        Err(_) => return true,
+    };
+
+    let config = &*CONFIG;
+    parsed_hash.algorithm != argon2::ARGON2ID_IDENT
+        || parsed_hash.version != Some(Version::V0x13.into())
+        || params.m_cost() != config.memory_kib
+        || params.t_cost() != config.iterations
+        || params.p_cost() != config.parallelism
+        || (params.keyid() == PEPPER_KEY_ID) != config.pepper.is_some()
+}
+
+
+fn argon2(pepper: Option<&[u8]>, params: Params) -> Result<Argon2<'_>, argon2::password_hash::Error> {
+    match pepper {
+        Some(pepper) => Ok(Argon2::new_with_secret(pepper, Algorithm::Argon2id, Version::V0x13, params)?),
+        None => Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params)),
+    }
+}
+
+fn env_value(name: &str, default: u32) -> u32 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::sync::LazyLock;
+use log::{error, info};
+use validator::{ValidationError, ValidationErrors};
+
+/*
+Password policy for new passwords
+
+PASSWORD_MIN_LENGTH       minimum length (default 8)
+PASSWORD_MAX_LENGTH       maximum length (default 128)
+PASSWORD_REQUIRE_UPPER    at least one upper case letter (default true)
+PASSWORD_REQUIRE_LOWER    at least one lower case letter (default true)
+PASSWORD_REQUIRE_DIGIT    at least one digit (default true)
+PASSWORD_REQUIRE_SYMBOL   at least one other character (default false)
+PASSWORD_BREACHED_LIST    file of breached or common passwords, one per line, checked on top of the bundled list
+*/
+
+struct PasswordPolicy {
+    min_length: usize,
+    max_length: usize,
+    require_upper: bool,
+    require_lower: bool,
+    require_digit: bool,
+    require_symbol: bool,
+}
+
+static POLICY: LazyLock<PasswordPolicy> = LazyLock::new(|| PasswordPolicy {
+    min_length: env_value("PASSWORD_MIN_LENGTH", 8),
+    max_length: env_value("PASSWORD_MAX_LENGTH", 128),
+    require_upper: env_value("PASSWORD_REQUIRE_UPPER", true),
+    require_lower: env_value("PASSWORD_REQUIRE_LOWER", true),
+    require_digit: env_value("PASSWORD_REQUIRE_DIGIT", true),
+    require_symbol: env_value("PASSWORD_REQUIRE_SYMBOL", false),
+});
+
+// lower cased, loaded once
+static BREACHED_PASSWORDS: LazyLock<HashSet<String>> = LazyLock::new(|| {
+    let mut passwords = parse_password_list(include_str!("../../resources/common_passwords.txt"));
+    if let Ok(path) = env::var("PASSWORD_BREACHED_LIST") {
+        match fs::read_to_string(&path) {
+            Ok(list) => passwords.extend(parse_password_list(&list)),
+            Err(e) => error!("Failed to read PASSWORD_BREACHED_LIST {}: {}", path, e),
+        }
+    }
+    info!("{} breached passwords loaded", passwords.len());
+    passwords
+});
+
+
+// check a new password, every violation comes back as an error of the password field
+pub fn validate_password(password: &str, name: &str, email: &str) -> Result<(), ValidationErrors> {
+    let policy = &*POLICY;
+    let mut violations = Vec::new();
+    let length = password.chars().count();
+
+    if length < policy.min_length {
+        violations.push(violation("too_short", format!("Password must be at least {} characters long", policy.min_length)));
+    }
+    if length > policy.max_length {
+        violations.push(violation("too_long", format!("Password must be at most {} characters long", policy.max_length)));
+    }
+    if policy.require_upper && !password.chars().any(|c| c.is_uppercase()) {
+        violations.push(violation("missing_upper", "Password must contain an upper case letter".to_string()));
+    }
+    if policy.require_lower && !password.chars().any(|c| c.is_lowercase()) {
+        violations.push(violation("missing_lower", "Password must contain a lower case letter".to_string()));
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push(violation("missing_digit", "Password must contain a digit".to_string()));
+    }
+    if policy.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+        violations.push(violation("missing_symbol", "Password must contain a symbol".to_string()));
+    }
+    if contains_personal_info(password, name, email) {
+        violations.push(violation("personal_info", "Password must not contain your name or email".to_string()));
+    }
+    if BREACHED_PASSWORDS.contains(&password.to_lowercase()) {
+        violations.push(violation("breached", "Password is too common or appeared in a data breach".to_string()));
+    }
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let mut errors = ValidationErrors::new();
+    for error in violations {
+        errors.add("password", error);
+    }
+    Err(errors)
+}
+
+
+fn env_value<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn parse_password_list(list: &str) -> HashSet<String> {
+    list.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_lowercase())
+        .collect()
+}
+
+fn violation(code: &'static str, message: String) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::from(message));
+    error
+}
+
+// parts of the name and the email shorter than 3 characters are ignored, they match too much
+fn contains_personal_info(password: &str, name: &str, email: &str) -> bool {
+    let password = password.to_lowercase();
+    let email_local = email.split('@').next().unwrap_or_default();
+
+    name.split_whitespace()
+        .chain(std::iter::once(name))
+        .chain(email_local.split(['.', '_', '-', '+']))
+        .chain(std::iter::once(email_local))
+        .map(|part| part.trim().to_lowercase())
+        .filter(|part| part.chars().count() >= 3)
+        .any(|part| password.contains(&part))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the tests run with the default policy, no PASSWORD_* variables set
+    fn violation_codes(password: &str, name: &str, email: &str) -> Vec<String> {
+        match validate_password(password, name, email) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.field_errors()["password"]
+                .iter()
+                .map(|error| error.code.to_string())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_a_password_matching_the_policy() {
+        assert!(violation_codes("Tr1cky-Horse-92", "Alice Smith", "alice@example.com").is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let codes = violation_codes("abc", "Alice Smith", "alice@example.com");
+        assert_eq!(codes, ["too_short", "missing_upper", "missing_digit"]);
+
+        let codes = violation_codes(&"Ab1".repeat(50), "Alice Smith", "alice@example.com");
+        assert_eq!(codes, ["too_long"]);
+    }
+
+    #[test]
+    fn rejects_name_and_email_parts() {
+        assert_eq!(violation_codes("Smith2024xy", "Alice Smith", "alice@example.com"), ["personal_info"]);
+        assert_eq!(violation_codes("Xjohnny99x", "Alice Smith", "johnny.b@example.com"), ["personal_info"]);
+    }
+
+    #[test]
+    fn ignores_short_name_parts() {
+        assert!(!contains_personal_info("Tr1cky-Horse-92", "Al Bo", "al@example.com"));
+    }
+
+    #[test]
+    fn rejects_breached_passwords_case_insensitively() {
+        assert_eq!(violation_codes("PASSword123", "Alice Smith", "alice@example.com"), ["breached"]);
+    }
+
+    #[test]
+    fn parses_password_lists() {
+        let list = parse_password_list("# comment\n\n  Secret1 \nsecret1\nhunter2\n");
+        assert_eq!(list, HashSet::from(["secret1".to_string(), "hunter2".to_string()]));
+    }
+}
@@ -0,0 +1,14 @@
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// generate a random opaque token (hex encoded)
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// hash a token before it is stored, only the hash is persisted
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
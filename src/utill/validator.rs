@@ -38,7 +38,41 @@ pub fn custom_text_check(value: &str) -> Result<(), ValidationError> {
     }
 }
 
+// course codes and searches for them, letters, digits, spaces and dashes
+pub fn custom_code_check(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty() {
+        return Ok(());
+    }
+
+    let regex_value = Regex::new(r"^[a-zA-Z0-9\s-]+$").unwrap();
+    if regex_value.is_match(value) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("Invalid code: Only letters, digits, spaces and dashes are allowed"))
+    }
+}
+
+// a single letter, e.g. the B of class 5B
+pub fn custom_section_letter_check(value: &str) -> Result<(), ValidationError> {
+    if value.len() == 1 && value.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("Invalid section: a single letter is expected"))
+    }
+}
+
+// two consecutive years, e.g. 2026-2027
+pub fn custom_academic_year_check(value: &str) -> Result<(), ValidationError> {
+    let years = value.split_once('-')
+        .and_then(|(start, end)| Some((start.parse::<u32>().ok()?, end.parse::<u32>().ok()?)));
+    match years {
+        Some((start, end)) if value.len() == 9 && end == start + 1 => Ok(()),
+        _ => Err(ValidationError::new("Invalid academic year: expected e.g. 2026-2027")),
+    }
+}
+
 // custom password validation
+#[allow(dead_code)]
 pub fn custom_uuid_check(value: &str) -> Result<(), ValidationError> {
     if Uuid::parse_str(value).is_ok() {
         Ok(())
@@ -0,0 +1,256 @@
+/*
+Single sign-on against a mock OpenID Connect provider
+
+Starts the server binary on localhost:8080 with the provider below as OIDC_ISSUER,
+against a scratch copy of the LMS database (the users and students tables have to exist):
+
+TEST_DB_URI=postgres://postgres@localhost:5432/lms_test cargo test --test oidc_login -- --ignored
+*/
+
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use actix_web::web::{Data, Form};
+use actix_web::{get, post, App, HttpResponse, HttpServer};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::{redirect, Client, StatusCode, Url};
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const APP: &str = "http://localhost:8080";
+const CLIENT_ID: &str = "lms-test";
+const KEY_ID: &str = "mock-key";
+
+// what the provider answers for an authorization code
+struct Grant {
+    code_challenge: String,
+    nonce: String,
+    email: String,
+    groups: Vec<String>,
+}
+
+struct MockProvider {
+    issuer: String,
+    signing_key: EncodingKey,
+    jwk: Value,
+    grants: Mutex<HashMap<String, Grant>>,
+}
+
+#[get("/.well-known/openid-configuration")]
+async fn discovery(provider: Data<MockProvider>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "issuer": provider.issuer,
+        "authorization_endpoint": format!("{}/authorize", provider.issuer),
+        "token_endpoint": format!("{}/token", provider.issuer),
+        "jwks_uri": format!("{}/jwks", provider.issuer),
+    }))
+}
+
+#[get("/jwks")]
+async fn jwks(provider: Data<MockProvider>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "keys": [provider.jwk] }))
+}
+
+// codes are good once and only with the verifier of the challenge sent to /authorize
+#[post("/token")]
+async fn token(provider: Data<MockProvider>, form: Form<HashMap<String, String>>) -> HttpResponse {
+    let grant = form.get("code").and_then(|code| provider.grants.lock().unwrap().remove(code));
+    let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+    let grant = match grant {
+        Some(grant) if form.get("client_id").map(String::as_str) == Some(CLIENT_ID)
+            && URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())) == grant.code_challenge => grant,
+        _ => return HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" })),
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let claims = json!({
+        "iss": provider.issuer,
+        "aud": CLIENT_ID,
+        "sub": Uuid::new_v4().to_string(),
+        "iat": now,
+        "exp": now + 300,
+        "nonce": grant.nonce,
+        "email": grant.email,
+        "email_verified": true,
+        "name": "Single Sign-On",
+        "groups": grant.groups,
+    });
+    let mut header = Header::new(Algorithm::ES256);
+    header.kid = Some(KEY_ID.to_string());
+    let id_token = encode(&header, &claims, &provider.signing_key).unwrap();
+    HttpResponse::Ok().json(json!({ "access_token": "mock", "token_type": "Bearer", "id_token": id_token }))
+}
+
+async fn start_provider() -> Data<MockProvider> {
+    let rng = SystemRandom::new();
+    let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+    let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng).unwrap();
+    // uncompressed point, 0x04 | x | y
+    let point = key_pair.public_key().as_ref();
+    let jwk = json!({
+        "kty": "EC",
+        "crv": "P-256",
+        "kid": KEY_ID,
+        "alg": "ES256",
+        "use": "sig",
+        "x": URL_SAFE_NO_PAD.encode(&point[1..33]),
+        "y": URL_SAFE_NO_PAD.encode(&point[33..]),
+    });
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let provider = Data::new(MockProvider {
+        issuer: format!("http://{}", listener.local_addr().unwrap()),
+        signing_key: EncodingKey::from_ec_der(pkcs8.as_ref()),
+        jwk,
+        grants: Mutex::new(HashMap::new()),
+    });
+
+    let app_data = provider.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(app_data.clone())
+            .service(discovery)
+            .service(jwks)
+            .service(token)
+    })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+    actix_web::rt::spawn(server);
+    provider
+}
+
+// the server binary, stopped when the test ends
+struct AppServer(Child);
+
+impl Drop for AppServer {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start_app(db_uri: &str, issuer: &str) -> AppServer {
+    let mail_dir = std::env::temp_dir().join(format!("oidc-test-mail-{}", Uuid::new_v4()));
+    let child = Command::new(env!("CARGO_BIN_EXE_framework-orm"))
+        .env("DB_URI", db_uri)
+        .env("SECRET_KEY", "oidc-test-secret")
+        .env("RUST_LOG", "warn")
+        .env("MAIL_DIR", mail_dir)
+        .env("OIDC_ISSUER", issuer)
+        .env("OIDC_CLIENT_ID", CLIENT_ID)
+        .env("OIDC_ROLE_MAPPING", "lms-teachers=Teacher")
+        .env("OIDC_DEFAULT_ROLE", "Student")
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("Failed to start the server binary");
+    let server = AppServer(child);
+
+    for _ in 0..300 {
+        if TcpStream::connect("localhost:8080").is_ok() {
+            return server;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    panic!("the server did not start on localhost:8080");
+}
+
+// the server allows a request every 2 seconds per ip
+async fn pace() {
+    actix_web::rt::time::sleep(Duration::from_millis(2100)).await;
+}
+
+// state, nonce and code challenge the server sends the browser to the provider with
+async fn start_login(client: &Client) -> (String, String, String) {
+    pace().await;
+    let response = client.get(format!("{}/oidc/login", APP)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+
+    let location = Url::parse(response.headers()["location"].to_str().unwrap()).unwrap();
+    let params: HashMap<String, String> = location.query_pairs().into_owned().collect();
+    assert_eq!(params["client_id"], CLIENT_ID);
+    assert_eq!(params["code_challenge_method"], "S256");
+    (params["state"].clone(), params["nonce"].clone(), params["code_challenge"].clone())
+}
+
+async fn callback(client: &Client, code: &str, state: &str) -> reqwest::Response {
+    pace().await;
+    client.get(format!("{}/oidc/callback", APP))
+        .query(&[("code", code), ("state", state)])
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn role_of(client: &Client, response: reqwest::Response) -> String {
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    let access_token = body["data"]["access_token"].as_str().unwrap();
+
+    pace().await;
+    let me: Value = client.get(format!("{}/me", APP)).bearer_auth(access_token).send().await.unwrap().json().await.unwrap();
+    me["data"]["role"].as_str().unwrap().to_string()
+}
+
+fn grant(provider: &MockProvider, code: &str, code_challenge: &str, nonce: &str, email: &str, groups: &[&str]) {
+    provider.grants.lock().unwrap().insert(code.to_string(), Grant {
+        code_challenge: code_challenge.to_string(),
+        nonce: nonce.to_string(),
+        email: email.to_string(),
+        groups: groups.iter().map(|group| group.to_string()).collect(),
+    });
+}
+
+#[actix_web::test]
+#[ignore = "starts the server binary, needs a database in TEST_DB_URI"]
+async fn single_sign_on_with_mock_provider() {
+    let db_uri = std::env::var("TEST_DB_URI").expect("TEST_DB_URI is not set");
+    let provider = start_provider().await;
+    let _app = start_app(&db_uri, &provider.issuer);
+    let client = Client::builder().redirect(redirect::Policy::none()).build().unwrap();
+
+    // state and PKCE round trip, the mapped group decides the role
+    let teacher_email = format!("sso-teacher-{}@example.com", Uuid::new_v4());
+    let (state, nonce, challenge) = start_login(&client).await;
+    grant(&provider, "code-teacher", &challenge, &nonce, &teacher_email, &["staff", "lms-teachers"]);
+    let response = callback(&client, "code-teacher", &state).await;
+    assert_eq!(role_of(&client, response).await, "Teacher");
+
+    // a state is used up by its first callback
+    grant(&provider, "code-reused", &challenge, &nonce, &teacher_email, &["lms-teachers"]);
+    assert_eq!(callback(&client, "code-reused", &state).await.status(), StatusCode::UNAUTHORIZED);
+
+    // an id token issued for another sign-in
+    let (state, _, challenge) = start_login(&client).await;
+    grant(&provider, "code-nonce", &challenge, "another-nonce", &teacher_email, &["lms-teachers"]);
+    assert_eq!(callback(&client, "code-nonce", &state).await.status(), StatusCode::UNAUTHORIZED);
+
+    // no mapped group, the default role applies
+    let student_email = format!("sso-student-{}@example.com", Uuid::new_v4());
+    let (state, nonce, challenge) = start_login(&client).await;
+    grant(&provider, "code-student", &challenge, &nonce, &student_email, &["staff"]);
+    let response = callback(&client, "code-student", &state).await;
+    assert_eq!(role_of(&client, response).await, "Student");
+
+    // accounts with two-factor authentication are not linked
+    let db = Database::connect(&db_uri).await.unwrap();
+    db.execute(Statement::from_sql_and_values(DbBackend::Postgres, "UPDATE users SET totp_enabled = true WHERE email = $1", [student_email.clone().into()]))
+        .await
+        .unwrap();
+    let (state, nonce, challenge) = start_login(&client).await;
+    grant(&provider, "code-totp", &challenge, &nonce, &student_email, &["staff"]);
+    assert_eq!(callback(&client, "code-totp", &state).await.status(), StatusCode::UNAUTHORIZED);
+
+    db.execute(Statement::from_sql_and_values(DbBackend::Postgres, "DELETE FROM users WHERE email IN ($1, $2)", [teacher_email.into(), student_email.into()]))
+        .await
+        .unwrap();
+}